@@ -47,6 +47,7 @@ pub struct AudioAssets<'a> {
     current_music_vol: f32,
     target_music_vol: f32,
     dist_cfg: DistanceVolume,
+    threat: f32, // 0..1, misma curva de distancia que el volumen (sin suavizar)
 
     /// Silencio tras spawn de enemigo (segundos).
     silence_timer: f32,
@@ -85,6 +86,7 @@ impl<'a> AudioAssets<'a> {
             current_music_vol: 0.0,
             target_music_vol: 0.0,
             dist_cfg: DistanceVolume::default(),
+            threat: 0.0,
             silence_timer: 0.0,
         })
     }
@@ -128,9 +130,16 @@ impl<'a> AudioAssets<'a> {
         }
 
         self.target_music_vol = vol;
+        self.threat = (vol / self.dist_cfg.max_vol.max(1e-3)).clamp(0.0, 1.0);
         self.smooth_monster(dt);
     }
 
+    /// Qué tan cerca está el monstruo (0..1), con la misma curva y oclusión
+    /// que el volumen de la capa MONSTER. Lo usa el pulso de pánico.
+    pub fn threat(&self) -> f32 {
+        self.threat
+    }
+
     fn smooth_monster(&mut self, dt: f32) {
         let rate = if self.target_music_vol > self.current_music_vol {
             self.dist_cfg.attack
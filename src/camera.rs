@@ -0,0 +1,103 @@
+// ======= Balanceo de cabeza =======
+const WALK_BOB_EYE: f32 = 0.018; // bloques de subida/bajada de los ojos al caminar
+const SPRINT_BOB_EYE: f32 = 0.032;
+const WALK_BOB_PITCH: f32 = 0.006; // rad de cabeceo por paso
+const SPRINT_BOB_PITCH: f32 = 0.012;
+const BOB_BLEND: f32 = 8.0; // qué tan rápido entra/sale el balanceo
+
+// ======= Sacudida por trauma =======
+const TRAUMA_DECAY: f32 = 0.9; // por segundo
+const NEAR_CELLS: f32 = 4.0; // a menos de esto el monstruo empieza a sacudir
+const NEAR_TRAUMA_RATE: f32 = 1.5; // trauma/seg con el monstruo encima
+const NEAR_TRAUMA_MAX: f32 = 0.55; // la cercanía sola no llega a sacudida total
+const SHAKE_PITCH: f32 = 0.05; // rad con trauma 1
+const SHAKE_EYE: f32 = 0.05; // bloques con trauma 1
+// ==================================
+
+/// Desplazamiento de la vista para este frame: lo suman `render3d` y los sprites.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ViewOffset {
+    pub pitch: f32, // rad, se suma al `pitch` del jugador (mueve el horizonte)
+    pub eye: f32,   // bloques, se suma a la altura de los ojos (0.5)
+}
+
+/// Efectos de cámara: balanceo al caminar/correr y sacudida por trauma.
+pub struct CameraFx {
+    bob: f32,    // 0..1, cuánto balanceo se aplica (se suaviza al arrancar/parar)
+    sprint: f32, // 0..1, mezcla entre balanceo de caminar y de correr
+    phase: f32,  // 0..1 dentro del paso actual
+    trauma: f32, // 0..1
+    time: f32,
+}
+
+impl CameraFx {
+    pub fn new() -> Self {
+        Self {
+            bob: 0.0,
+            sprint: 0.0,
+            phase: 0.0,
+            trauma: 0.0,
+            time: 0.0,
+        }
+    }
+
+    /// Vuelve al reposo (al empezar o reintentar un nivel).
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Suma trauma (p. ej. 1.0 cuando el monstruo te atrapa).
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    /// `stride_phase` es el avance dentro del paso (el acumulador de pasos
+    /// de `main` dividido por la zancada): el pie toca el piso en 0 y 1.
+    /// `enemy_cells` es la distancia al monstruo en celdas, si lo hay.
+    pub fn update(
+        &mut self,
+        dt: f32,
+        stride_phase: f32,
+        walking: bool,
+        sprinting: bool,
+        enemy_cells: Option<f32>,
+    ) {
+        self.time += dt;
+        let k = (BOB_BLEND * dt).min(1.0);
+        self.bob += ((walking as u8 as f32) - self.bob) * k;
+        self.sprint += ((sprinting as u8 as f32) - self.sprint) * k;
+        self.phase = stride_phase.clamp(0.0, 1.0);
+
+        // el monstruo cerca sube el trauma hasta un tope
+        if let Some(d) = enemy_cells.filter(|&d| d < NEAR_CELLS) {
+            let closeness = 1.0 - d / NEAR_CELLS;
+            let near = self.trauma + NEAR_TRAUMA_RATE * closeness * dt;
+            self.trauma = self.trauma.max(near.min(NEAR_TRAUMA_MAX));
+        }
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.0);
+    }
+
+    /// Desplazamiento de la vista para este frame.
+    pub fn offset(&self) -> ViewOffset {
+        // balanceo: ojos abajo al pisar, arriba a mitad de paso
+        let step = -(self.phase * std::f32::consts::TAU).cos();
+        let eye_amp = lerp(WALK_BOB_EYE, SPRINT_BOB_EYE, self.sprint);
+        let pitch_amp = lerp(WALK_BOB_PITCH, SPRINT_BOB_PITCH, self.sprint);
+
+        // sacudida: trauma² para que lo leve casi no se note
+        let shake = self.trauma * self.trauma;
+        let t = self.time;
+        let n1 = (t * 37.0).sin() * 0.6 + (t * 71.0 + 1.3).sin() * 0.4;
+        let n2 = (t * 43.0 + 2.1).sin() * 0.6 + (t * 89.0 + 0.7).sin() * 0.4;
+
+        ViewOffset {
+            pitch: self.bob * pitch_amp * step + shake * SHAKE_PITCH * n1,
+            eye: self.bob * eye_amp * step + shake * SHAKE_EYE * n2,
+        }
+    }
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
@@ -1,60 +1,192 @@
-use crate::framebuffer::FrameBuffer;
-use crate::maze::Maze;
+use crate::maze::{Maze, ThinWall};
 use crate::player::Player;
-use raylib::prelude::*;
+
+/// Cara de la celda golpeada (en coordenadas de pantalla: y crece hacia abajo).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Face {
+    North, // borde superior de la celda (rayo bajando)
+    South, // borde inferior (rayo subiendo)
+    East,  // borde derecho (rayo hacia la izquierda)
+    West,  // borde izquierdo (rayo hacia la derecha)
+}
+
+impl Face {
+    /// `true` si la cara está sobre una línea vertical de la grilla (E/W).
+    #[inline]
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Face::East | Face::West)
+    }
+}
 
 pub struct Hit {
-    pub distance: f32,
+    pub distance: f32,      // distancia euclidiana (px) desde el jugador
+    pub perp_distance: f32, // distancia perpendicular al plano de cámara (px)
     pub impact: char,
     pub hit_x: f32,
     pub hit_y: f32,
+    pub cell: (i32, i32),
+    pub face: Face,
+    pub slide: f32,  // corrimiento de la textura en u (puertas a medio abrir)
+    pub jamb: bool,  // costado del vano de una pared fina: va con la textura de jamba
+    pub height: f32, // alto de la superficie en bloques (1 = del piso al techo)
+    pub exit: f32,   // distancia perpendicular (px) a la que el rayo sale de la celda
 }
 
-// Se detiene en la primera SUPERFICIE: muro, puerta C cerrada, E o F
-pub fn cast_ray_topdown(
-    framebuffer: &mut FrameBuffer,
-    maze: &Maze,
-    player: &Player,
-    angle: f32,
-    draw_line: bool,
-) -> Hit {
-    let mut d = 0.0_f32;
-    let step = 2.0_f32;
+/// Lo que un rayo atravesó hasta la superficie opaca.
+#[derive(Default)]
+pub struct RayTrace {
+    pub layers: Vec<Hit>,       // rejas, ventanas y muros bajos, de cerca a lejos
+    pub cells: Vec<(i32, i32)>, // celdas recorridas, incluida la del impacto
+}
 
+// DDA exacto sobre la grilla: se detiene en la primera SUPERFICIE opaca de
+// altura completa (muro, E, F o una pared fina como la losa de una puerta C).
+// De la celda del jugador solo se evalúa la pared fina, si la hay.
+// Las paredes finas que se ven a través (rejas, ventanas) y los muros bajos
+// no lo detienen: se anotan en `trace.layers`, del más cercano al más lejano,
+// y el rayo sigue. Las celdas que recorre quedan en `trace.cells`.
+// No toca el framebuffer, así que se puede llamar desde varios hilos.
+pub fn cast_ray(maze: &Maze, player: &Player, angle: f32, trace: &mut RayTrace) -> Hit {
+    let bs = maze.block_size as f32;
+    let dir_x = angle.cos();
+    let dir_y = angle.sin();
+
+    // Origen en unidades de celda
+    let ox = player.pos.x / bs;
+    let oy = player.pos.y / bs;
+    let mut ci = ox.floor() as i32;
+    let mut cj = oy.floor() as i32;
+
+    // Avance del parámetro t (en celdas) para cruzar una celda completa en x / y
+    let delta_x = if dir_x == 0.0 { f32::INFINITY } else { (1.0 / dir_x).abs() };
+    let delta_y = if dir_y == 0.0 { f32::INFINITY } else { (1.0 / dir_y).abs() };
+
+    // t hasta la primera línea vertical / horizontal de la grilla
+    let (step_i, mut side_x) = if dir_x < 0.0 {
+        (-1, (ox - ci as f32) * delta_x)
+    } else if dir_x > 0.0 {
+        (1, (ci as f32 + 1.0 - ox) * delta_x)
+    } else {
+        (0, f32::INFINITY)
+    };
+    let (step_j, mut side_y) = if dir_y < 0.0 {
+        (-1, (oy - cj as f32) * delta_y)
+    } else if dir_y > 0.0 {
+        (1, (cj as f32 + 1.0 - oy) * delta_y)
+    } else {
+        (0, f32::INFINITY)
+    };
+
+    // Fuera del mapa `tile_at` devuelve '#', así que el bucle siempre termina.
+    let ray = ThinRay {
+        ox,
+        oy,
+        dir_x,
+        dir_y,
+    };
+    let cos_delta = (angle - player.a).cos().abs().max(1e-6);
+    let hit_at = |t: f32, t_out: f32, ci: i32, cj: i32, face: Face, slide: f32, jamb: bool| {
+        let distance = t * bs;
+        Hit {
+            distance,
+            perp_distance: distance * cos_delta,
+            impact: maze.tile_at(ci, cj),
+            hit_x: player.pos.x + dir_x * distance,
+            hit_y: player.pos.y + dir_y * distance,
+            cell: (ci, cj),
+            face,
+            slide,
+            jamb,
+            height: maze.wall_height(ci, cj),
+            exit: t_out * bs * cos_delta,
+        }
+    };
+
+    let mut t_enter = 0.0;
+    trace.cells.push((ci, cj));
     loop {
-        let x = player.pos.x + angle.cos() * d;
-        let y = player.pos.y + angle.sin() * d;
-
-        if x < 0.0
-            || y < 0.0
-            || x >= (maze.width * maze.block_size as usize) as f32
-            || y >= (maze.height * maze.block_size as usize) as f32
-        {
-            return Hit {
-                distance: d,
-                impact: '#',
-                hit_x: x,
-                hit_y: y,
-            };
+        let thin = maze.thin_wall_at(ci, cj);
+        let t_exit = side_x.min(side_y);
+        let thin_hit = thin.and_then(|w| Some((w, ray.hit(w, ci, cj, t_enter, t_exit)?)));
+        if let Some((w, (t, face, slide))) = thin_hit {
+            let hit = hit_at(t, t, ci, cj, face, slide, false);
+            if !w.see_through {
+                return hit;
+            }
+            trace.layers.push(hit);
         }
 
-        let ci = (x / maze.block_size as f32) as i32;
-        let cj = (y / maze.block_size as f32) as i32;
+        let (t, face) = if side_x < side_y {
+            let t = side_x;
+            side_x += delta_x;
+            ci += step_i;
+            (t, if step_i > 0 { Face::West } else { Face::East })
+        } else {
+            let t = side_y;
+            side_y += delta_y;
+            cj += step_j;
+            (t, if step_j > 0 { Face::North } else { Face::South })
+        };
+        trace.cells.push((ci, cj));
 
         if maze.is_surface_at(ci, cj) {
-            return Hit {
-                distance: d,
-                impact: maze.tile_at(ci, cj),
-                hit_x: x,
-                hit_y: y,
-            };
+            // saliendo del vano de una puerta hacia un costado: es el marco
+            // (jamba), no el muro de siempre
+            let jamb = thin.is_some_and(|w| !w.see_through && face.is_vertical() != w.along_y);
+            let hit = hit_at(t, side_x.min(side_y), ci, cj, face, 0.0, jamb);
+            if hit.height >= 1.0 {
+                return hit;
+            }
+            // muro bajo: se ve por encima, así que se anota y el rayo sigue
+            trace.layers.push(hit);
         }
+        t_enter = t;
+    }
+}
 
-        if draw_line {
-            framebuffer.set_color(Color::WHITE);
-            framebuffer.set_pixel(x as i32, y as i32);
-        }
+/// Rayo en unidades de celda, para cortar las paredes finas.
+struct ThinRay {
+    ox: f32,
+    oy: f32,
+    dir_x: f32,
+    dir_y: f32,
+}
 
-        d += step;
+impl ThinRay {
+    /// Si el rayo (que recorre la celda (i, j) entre `t_enter` y `t_exit`)
+    /// choca con la parte de la pared fina que sigue fuera del muro,
+    /// devuelve (t, cara, corrimiento de textura).
+    fn hit(
+        &self,
+        wall: ThinWall,
+        i: i32,
+        j: i32,
+        t_enter: f32,
+        t_exit: f32,
+    ) -> Option<(f32, Face, f32)> {
+        // plano en la mitad de la celda y coordenada a lo largo de él
+        let (t, s, face) = if wall.along_y {
+            let t = (i as f32 + 0.5 - self.ox) / self.dir_x;
+            let face = if self.dir_x > 0.0 {
+                Face::West
+            } else {
+                Face::East
+            };
+            (t, self.oy + self.dir_y * t - j as f32, face)
+        } else {
+            let t = (j as f32 + 0.5 - self.oy) / self.dir_y;
+            let face = if self.dir_y > 0.0 {
+                Face::North
+            } else {
+                Face::South
+            };
+            (t, self.ox + self.dir_x * t - i as f32, face)
+        };
+        // la losa ocupa [0, 1 - open] y se mete en el muro por el lado s = 0
+        if t.is_finite() && t >= t_enter && t < t_exit && s >= 0.0 && s < 1.0 - wall.open {
+            Some((t, face, wall.open))
+        } else {
+            None
+        }
     }
 }
@@ -1,14 +1,16 @@
-use crate::{maze::Maze, player::Player};
+use crate::{maze::Maze, player::Player, settings::Settings};
 use raylib::prelude::*;
 use raylib::prelude::{GamepadAxis, GamepadButton};
 
 const PLAYER_RADIUS: f32 = 10.0;
 const MOUSE_SENS: f32 = 0.0035;
+const MAX_PITCH: f32 = 0.4; // rad: límite para mirar arriba / abajo
 
 // ======= Parámetros de gamepad =======
 const GAMEPAD_ID: i32 = 0;            // primer control
 const STICK_DEADZONE: f32 = 0.18;     // zona muerta para sticks
 const PAD_YAW_SENS: f32 = 2.6;        // rad/seg de giro a deflexión 1.0
+const PAD_PITCH_SENS: f32 = 1.6;      // rad/seg de cabeceo a deflexión 1.0
 const PAD_RUN_THRESHOLD: f32 = 0.55;  // umbral de LT/RT para sprint
 // =====================================
 
@@ -23,7 +25,7 @@ fn collides(maze: &Maze, x: f32, y: f32, r: f32) -> bool {
     for (tx, ty) in tests {
         let i = (tx / bs) as isize;
         let j = (ty / bs) as isize;
-        if maze.is_blocking_at(i, j) {
+        if maze.blocks_movement_at(i, j) {
             return true;
         }
     }
@@ -59,16 +61,30 @@ fn dz(x: f32, dead: f32) -> f32 {
 }
 
 /// Devuelve `true` si se abrió/cerró alguna puerta este frame.
-pub fn process_input(rl: &RaylibHandle, player: &mut Player, maze: &mut Maze, dt: f32) -> bool {
+pub fn process_input(
+    rl: &RaylibHandle,
+    player: &mut Player,
+    maze: &mut Maze,
+    settings: &Settings,
+    dt: f32,
+) -> bool {
     // -------- Rotación (mouse + stick derecho) --------
     let md = rl.get_mouse_delta();
     let mut yaw_delta = md.x * MOUSE_SENS;
+    let mut pitch_delta = -md.y * MOUSE_SENS;
 
     if rl.is_gamepad_available(GAMEPAD_ID) {
-        // Giro con stick derecho (eje X)
+        // Giro con stick derecho (eje X) y cabeceo (eje Y)
         let rx = rl.get_gamepad_axis_movement(GAMEPAD_ID, GamepadAxis::GAMEPAD_AXIS_RIGHT_X);
+        let ry = rl.get_gamepad_axis_movement(GAMEPAD_ID, GamepadAxis::GAMEPAD_AXIS_RIGHT_Y);
+        yaw_delta += dz(rx, STICK_DEADZONE) * PAD_YAW_SENS * dt;
+        pitch_delta -= dz(ry, STICK_DEADZONE) * PAD_PITCH_SENS * dt;
+    }
+    if settings.invert_y {
+        pitch_delta = -pitch_delta;
     }
     player.a = normalize_angle(player.a + yaw_delta);
+    player.pitch = (player.pitch + pitch_delta).clamp(-MAX_PITCH, MAX_PITCH);
 
     // -------- Movimiento (teclado + stick izquierdo) --------
     let mut dir = 0.0;
@@ -96,6 +112,7 @@ pub fn process_input(rl: &RaylibHandle, player: &mut Player, maze: &mut Maze, dt
         }
     }
 
+    player.sprinting = sprint;
     let speed = if sprint { player.move_speed * 1.8 } else { player.move_speed };
 
     let forward_dx = player.a.cos() * speed * dir * dt;
@@ -105,6 +122,16 @@ pub fn process_input(rl: &RaylibHandle, player: &mut Player, maze: &mut Maze, dt
 
     try_move(player, maze, forward_dx + strafe_dx, forward_dy + strafe_dy);
 
+    // -------- Linterna (F o botón Y / △ del gamepad) --------
+    if rl.is_key_pressed(KeyboardKey::KEY_F) {
+        player.flashlight.toggle();
+    }
+    if rl.is_gamepad_available(GAMEPAD_ID)
+        && rl.is_gamepad_button_pressed(GAMEPAD_ID, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP)
+    {
+        player.flashlight.toggle();
+    }
+
     // -------- Usar puerta (E o botón A del gamepad) --------
     let mut toggled = false;
     if rl.is_key_pressed(KeyboardKey::KEY_E) {
@@ -119,3 +146,10 @@ pub fn process_input(rl: &RaylibHandle, player: &mut Player, maze: &mut Maze, dt
 
     toggled
 }
+
+/// Abrir / cerrar el mapa completo (Tab o Select / Back del gamepad).
+pub fn map_toggle_pressed(rl: &RaylibHandle) -> bool {
+    rl.is_key_pressed(KeyboardKey::KEY_TAB)
+        || (rl.is_gamepad_available(GAMEPAD_ID)
+            && rl.is_gamepad_button_pressed(GAMEPAD_ID, GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT))
+}
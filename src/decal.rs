@@ -0,0 +1,48 @@
+use crate::caster::Face;
+
+/// Calcomanía sobre una o más caras de un muro (sangre, carteles, números
+/// de sala...): una textura con alpha que se compone encima de la del muro.
+#[derive(Clone, Debug)]
+pub struct Decal {
+    pub key: char,        // clave de textura (PNG con alpha, cubre la cara entera)
+    pub cell: (i32, i32), // celda del muro
+    pub faces: Vec<Face>, // caras que la llevan
+}
+
+impl Decal {
+    /// Parsea una línea de `[decals]`: `clave i j caras`, con la celda del
+    /// muro y las caras como letras `n s e w` (p. ej. `x 22 9 ew`).
+    pub fn parse(line: &str) -> Result<Option<Self>, String> {
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [key, i, j, faces] = parts[..] else {
+            return Err(format!("decal inválido (clave i j caras): '{}'", line));
+        };
+        let mut chars = key.chars();
+        let (Some(key), None) = (chars.next(), chars.next()) else {
+            return Err(format!("decal sin clave de textura: '{}'", line));
+        };
+        let num = |s: &str| {
+            s.parse::<i32>()
+                .map_err(|_| format!("celda inválida en [decals]: '{}'", s))
+        };
+        let faces = faces
+            .chars()
+            .map(|c| match c {
+                'n' => Ok(Face::North),
+                's' => Ok(Face::South),
+                'e' => Ok(Face::East),
+                'w' => Ok(Face::West),
+                _ => Err(format!("cara inválida en [decals] (n s e w): '{}'", c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(Self {
+            key,
+            cell: (num(i)?, num(j)?),
+            faces,
+        }))
+    }
+}
@@ -0,0 +1,83 @@
+const OPEN_SECS: f32 = 0.6; // lo que tarda en abrirse o cerrarse del todo
+const AUTO_CLOSE_SECS: f32 = 1.5; // abierta del todo, se cierra sola tras esto
+const RETRY_CLOSE_SECS: f32 = 0.4; // si hay alguien en el vano, vuelve a intentar
+const PASSABLE_OPEN: f32 = 0.8; // desde esta fracción ya no bloquea
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DoorState {
+    Closed,
+    Opening,
+    Open,
+    Closing,
+}
+
+/// Puerta corrediza de una celda 'C': una losa fina en la mitad de la celda
+/// que se mete dentro del muro al abrirse.
+#[derive(Clone, Debug)]
+pub struct Door {
+    pub state: DoorState,
+    pub open: f32,     // 0 = cerrada, 1 = metida del todo en el muro
+    pub along_y: bool, // la losa va sobre x = i + 0.5 (pasillo este-oeste) y corre en y
+    timer: f32,
+}
+
+impl Door {
+    pub fn new(along_y: bool) -> Self {
+        Self {
+            state: DoorState::Closed,
+            open: 0.0,
+            along_y,
+            timer: 0.0,
+        }
+    }
+
+    /// `true` si ya se puede pasar (o el rayo de la IA / audio la atraviesa).
+    #[inline]
+    pub fn is_passable(&self) -> bool {
+        self.open >= PASSABLE_OPEN
+    }
+
+    /// Usar la puerta: si está cerrada o cerrándose se abre; si no, se cierra.
+    pub fn toggle(&mut self) {
+        self.state = match self.state {
+            DoorState::Closed | DoorState::Closing => DoorState::Opening,
+            DoorState::Open | DoorState::Opening => DoorState::Closing,
+        };
+    }
+
+    /// Avanza la animación. `occupied` = alguien está en el vano: en ese caso
+    /// la puerta no se cierra encima (se reabre, como un ascensor).
+    pub fn update(&mut self, dt: f32, occupied: bool) {
+        let step = dt / OPEN_SECS;
+        match self.state {
+            DoorState::Closed => {}
+            DoorState::Opening => {
+                self.open = (self.open + step).min(1.0);
+                if self.open >= 1.0 {
+                    self.state = DoorState::Open;
+                    self.timer = AUTO_CLOSE_SECS;
+                }
+            }
+            DoorState::Open => {
+                self.timer -= dt;
+                if self.timer <= 0.0 {
+                    if occupied {
+                        self.timer = RETRY_CLOSE_SECS;
+                    } else {
+                        self.state = DoorState::Closing;
+                    }
+                }
+            }
+            DoorState::Closing => {
+                if occupied {
+                    self.state = DoorState::Opening;
+                    return;
+                }
+                self.open = (self.open - step).max(0.0);
+                if self.open <= 0.0 {
+                    self.state = DoorState::Closed;
+                }
+            }
+        }
+    }
+}
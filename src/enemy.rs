@@ -1,6 +1,9 @@
+use crate::sprites::Sprite;
+use crate::textures::Textures;
 use crate::{framebuffer::FrameBuffer, maze::Maze, player::Player};
 use raylib::prelude::*;
 use std::collections::{HashMap, VecDeque};
+use std::f32::consts::{PI, TAU};
 
 const ENEMY_RADIUS: f32 = 10.0;
 const ENEMY_SPEED: f32 = 95.0;
@@ -13,16 +16,34 @@ const SPAWN_GRACE_SECS: f32 = 1.2;
 const RETREAT_UNREACHABLE_SECS: f32 = 2.5;
 
 const BOX_HALF: f32 = 0.35;
+const TURN_RATE: f32 = 10.0; // qué tan rápido gira hacia su dirección de avance
 
 pub struct Enemy {
     pub pos: Vector2,
+    pub heading: f32, // rad, según hacia dónde se movió por última vez
     path: Vec<(i32, i32)>,
     time_to_repath: f32,
     awake: f32,
     frustration: f32,
+    anim: &'static str, // animación actual de la hoja 'M' ("idle", "walk", "lunge")
+    anim_time: f32,
 }
 
 impl Enemy {
+    /// Enemigo quieto en `pos`, mirando hacia `look_at`.
+    pub fn at(pos: Vector2, look_at: Vector2) -> Self {
+        Self {
+            pos,
+            heading: heading_towards(pos, look_at),
+            path: Vec::new(),
+            time_to_repath: 0.0,
+            awake: SPAWN_GRACE_SECS,
+            frustration: 0.0,
+            anim: "idle",
+            anim_time: 0.0,
+        }
+    }
+
     pub fn spawn_from_map_or_far(maze: &Maze, player: &Player) -> Self {
         let bs = maze.block_size as f32;
         let start = Self::cell_of(player.pos, bs);
@@ -31,13 +52,8 @@ impl Enemy {
         if let Some((ti, tj)) = maze.find_first('T') {
             let d = distmap.get(&(ti, tj)).copied().unwrap_or(-1);
             if d >= MIN_SPAWN_DIST_CELLS {
-                return Self {
-                    pos: Vector2::new((ti as f32 + 0.5) * bs, (tj as f32 + 0.5) * bs),
-                    path: Vec::new(),
-                    time_to_repath: 0.0,
-                    awake: SPAWN_GRACE_SECS,
-                    frustration: 0.0,
-                };
+                let pos = Vector2::new((ti as f32 + 0.5) * bs, (tj as f32 + 0.5) * bs);
+                return Self::at(pos, player.pos);
             }
         }
         let mut best: Option<((i32, i32), i32)> = None;
@@ -58,14 +74,7 @@ impl Enemy {
             (spawn_cell.0 as f32 + 0.5) * bs,
             (spawn_cell.1 as f32 + 0.5) * bs,
         );
-
-        Self {
-            pos: spawn,
-            path: Vec::new(),
-            time_to_repath: 0.0,
-            awake: SPAWN_GRACE_SECS,
-            frustration: 0.0,
-        }
+        Self::at(spawn, player.pos)
     }
 
     fn retreat_far_from_player(&mut self, maze: &Maze, player: &Player) {
@@ -95,8 +104,10 @@ impl Enemy {
     }
 
     pub fn update(&mut self, maze: &Maze, player: &Player, dt: f32) -> bool {
+        self.anim_time += dt;
         if self.awake > 0.0 {
             self.awake -= dt;
+            self.set_anim("idle");
             return false;
         }
         if self.pos.distance_to(player.pos) <= (KILL_DIST + ENEMY_RADIUS) {
@@ -104,7 +115,7 @@ impl Enemy {
         }
 
         let bs = maze.block_size as f32;
-        let chase = has_los(maze, self.pos, player.pos);
+        let chase = has_los(maze, self.pos, player.pos, maze.see_over_low);
         let spd = if chase {
             ENEMY_CHASE_SPEED
         } else {
@@ -143,104 +154,54 @@ impl Enemy {
         } else {
             self.frustration = 0.0;
         }
+        // una sola vez por frame: cambiarla reinicia su reloj
+        let anim = if chase {
+            "lunge"
+        } else if self.path.is_empty() {
+            "idle"
+        } else {
+            "walk"
+        };
+        self.set_anim(anim);
 
         let (dx, dy) = dir_towards(self.pos, target);
         let step = spd * dt;
+        let before = self.pos;
         try_move_enemy(self, maze, dx * step, dy * step);
+        self.turn_towards_motion(before, dt);
 
         self.pos.distance_to(player.pos) <= (KILL_DIST + ENEMY_RADIUS)
     }
 
-    /// ===== Sprite billboard con textura (PNG con alpha) =====
-    pub fn render_sprite3d(
-        &self,
-        framebuffer: &mut FrameBuffer,
-        maze: &Maze,
-        player: &Player,
-        zbuffer: &mut [f32],
-        textures: &crate::textures::Textures,
-    ) {
-        let w = framebuffer.width as i32;
-        let h = framebuffer.height as i32;
-        let hw = w as f32 * 0.5;
-        let hh = h as f32 * 0.5;
-        let dist_to_proj = hw / (player.fov * 0.5).tan();
-        let bs = maze.block_size as f32;
-
-        // dirección adelante y derecha de la cámara
-        let dirx = player.a.cos();
-        let diry = player.a.sin();
-        let rightx = -diry;
-        let righty = dirx;
-        let plane_len = (player.fov * 0.5).tan();
-
-        // vector desde cámara a enemigo
-        let vx = self.pos.x - player.pos.x;
-        let vy = self.pos.y - player.pos.y;
-
-        // componente hacia adelante (perpendicular a pantalla)
-        let perp = vx * dirx + vy * diry;
-        if perp <= 1.0 {
-            return;
-        } // detrás o demasiado cerca
-
-        // componente lateral (para x en pantalla)
-        let side = vx * rightx + vy * righty;
-        let screen_x = hw * (1.0 + (side / (perp * plane_len)));
-
-        // altura del sprite en pantalla (≈ tamaño de un bloque)
-        let sprite_h = (bs * dist_to_proj) / perp;
-        let tex = textures.get('M');
-        let sprite_w = sprite_h * (tex.w as f32 / tex.h as f32);
-
-        let mut x0 = (screen_x - sprite_w * 0.5).floor() as i32;
-        let mut x1 = (screen_x + sprite_w * 0.5).ceil() as i32;
-        let mut y0 = (hh - sprite_h * 0.5).floor() as i32;
-        let mut y1 = (hh + sprite_h * 0.5).ceil() as i32;
-
-        // recorta a pantalla
-        x0 = x0.clamp(0, w - 1);
-        x1 = x1.clamp(0, w - 1);
-        y0 = y0.clamp(0, h - 1);
-        y1 = y1.clamp(0, h - 1);
-        if x0 > x1 || y0 > y1 {
+    /// Gira suavemente hacia donde se desplazó desde `before`.
+    fn turn_towards_motion(&mut self, before: Vector2, dt: f32) {
+        if self.pos.distance_to(before) < 1e-3 {
             return;
         }
+        let target = heading_towards(before, self.pos);
+        let diff = (target - self.heading + PI).rem_euclid(TAU) - PI;
+        self.heading += diff * (TURN_RATE * dt).min(1.0);
+    }
 
-        let u_eps = 0.5 / tex.w as f32;
-        let v_eps = 0.5 / tex.h as f32;
-
-        // niebla leve
-        let sky = Color::new(20, 24, 40, 255);
-        let fog_t = 1.0 - (-perp * 0.010).exp();
-
-        for sx in x0..=x1 {
-            let col = sx as usize;
-            if col < zbuffer.len() && perp >= zbuffer[col] {
-                continue;
-            }
-
-            let u =
-                ((sx as f32 - (screen_x - sprite_w * 0.5)) / sprite_w).clamp(u_eps, 1.0 - u_eps);
-
-            for sy in y0..=y1 {
-                let v = ((sy as f32 - (hh - sprite_h * 0.5)) / sprite_h).clamp(v_eps, 1.0 - v_eps);
-
-                let c = tex.sample(u, v);
-                if c.a < 16 {
-                    continue;
-                } 
+    /// Cambia de animación reiniciando su reloj (si ya estaba, no hace nada).
+    fn set_anim(&mut self, name: &'static str) {
+        if self.anim != name {
+            self.anim = name;
+            self.anim_time = 0.0;
+        }
+    }
 
-                let mut out = c;
-                out.r = (out.r as f32 * (1.0 - fog_t) + sky.r as f32 * fog_t) as u8;
-                out.g = (out.g as f32 * (1.0 - fog_t) + sky.g as f32 * fog_t) as u8;
-                out.b = (out.b as f32 * (1.0 - fog_t) + sky.b as f32 * fog_t) as u8;
+    /// `true` si hay línea de vista desde `from` (para mostrarlo en el mapa).
+    pub fn visible_from(&self, maze: &Maze, from: Vector2) -> bool {
+        has_los(maze, from, self.pos, maze.see_over_low)
+    }
 
-                framebuffer.set_color(out);
-                framebuffer.set_pixel(sx, sy);
-            }
-            zbuffer[col] = perp;
-        }
+    /// Billboard del enemigo para `render_sprites`, con el frame de su animación.
+    pub fn sprite(&self, textures: &Textures) -> Sprite {
+        let mut s = Sprite::new('M', self.pos);
+        s.frame = textures.get('M').animation_frame(self.anim, self.anim_time);
+        s.heading = Some(self.heading);
+        s
     }
 
     // --- utilidades ---
@@ -271,10 +232,6 @@ impl Enemy {
             if let Some(t_hit) = ray_aabb_2d(player.pos, dir, minx, maxx, miny, maxy) {
                 let delta = (ray_angle - player.a).cos().abs().max(1e-6);
                 let dist_perp = t_hit * delta;
-                let col = sx as usize;
-                if col < zbuffer.len() && dist_perp >= zbuffer[col] {
-                    continue;
-                }
                 let stake_h = (maze.block_size as f32 * dist_to_proj) / dist_perp;
                 let top = ((hh - stake_h * 0.5).max(0.0)) as i32;
                 let bot = ((hh + stake_h * 0.5).min(framebuffer.height as f32 - 1.0)) as i32;
@@ -287,9 +244,14 @@ impl Enemy {
                 );
                 framebuffer.set_color(c);
                 for y in top..=bot {
+                    // z-buffer por píxel, como el de `render3d`
+                    let idx = (y * w + sx) as usize;
+                    if idx >= zbuffer.len() || dist_perp >= zbuffer[idx] {
+                        continue;
+                    }
                     framebuffer.set_pixel(sx, y);
+                    zbuffer[idx] = dist_perp;
                 }
-                zbuffer[col] = dist_perp;
             }
         }
     }
@@ -341,6 +303,10 @@ fn ray_aabb_2d(
 
 /* -------------------- IA / movimiento / pathfinding -------------------- */
 
+fn heading_towards(from: Vector2, to: Vector2) -> f32 {
+    (to.y - from.y).atan2(to.x - from.x)
+}
+
 fn dir_towards(from: Vector2, to: Vector2) -> (f32, f32) {
     let mut v = to - from;
     let len = (v.x * v.x + v.y * v.y).sqrt();
@@ -372,14 +338,15 @@ fn collides(maze: &Maze, x: f32, y: f32, r: f32) -> bool {
     for (tx, ty) in tests {
         let i = (tx / bs) as isize;
         let j = (ty / bs) as isize;
-        if maze.is_blocking_at(i, j) {
+        if maze.blocks_movement_at(i, j) {
             return true;
         }
     }
     false
 }
 
-fn has_los(maze: &Maze, from: Vector2, to: Vector2) -> bool {
+/// Línea de vista; con `see_over_low` los muros bajos no la cortan.
+fn has_los(maze: &Maze, from: Vector2, to: Vector2, see_over_low: bool) -> bool {
     let bs = maze.block_size as f32;
     let mut d = 0.0f32;
     let total = from.distance_to(to);
@@ -393,7 +360,7 @@ fn has_los(maze: &Maze, from: Vector2, to: Vector2) -> bool {
         let p = from + dir * d;
         let ci = (p.x / bs) as isize;
         let cj = (p.y / bs) as isize;
-        if maze.is_blocking_at(ci, cj) {
+        if maze.blocks_sight_at(ci, cj, see_over_low) {
             return false;
         }
         d += step;
@@ -407,7 +374,7 @@ fn is_walkable_cell(maze: &Maze, i: i32, j: i32) -> bool {
     }
     let c = maze.tile_at(i, j);
     match c {
-        '#' | 'A' | 'B' => false,
+        '#' | 'A' | 'B' | 'R' | 'V' | 'H' | 'K' => false,
         'C' => maze.door_is_open(i as usize, j as usize),
         'T' => true,
         _ => true, // '.', 'E', 'F', 'P'
@@ -0,0 +1,183 @@
+use crate::lightmap::{LightMap, Rgb};
+use crate::maze::Maze;
+use crate::player::Player;
+use raylib::prelude::*;
+
+const DRAIN_PER_SEC: f32 = 1.0 / 120.0; // ~2 min de batería encendida
+const RECHARGE_PER_SEC: f32 = 1.0 / 60.0; // se recarga sola mientras está apagada
+const LOW_BATTERY: f32 = 0.15; // por debajo parpadea
+const MAX_LIGHT: f32 = 1.6; // tope por canal al sumar lámparas cercanas
+
+/// Linterna del jugador: cono con caída angular y atenuación por distancia.
+pub struct Flashlight {
+    pub on: bool,
+    pub battery: f32,   // 0..1
+    pub inner_cos: f32, // coseno del medio-ángulo con luz plena
+    pub outer_cos: f32, // coseno del medio-ángulo donde se apaga el borde
+    pub range: f32,     // celdas: a esta distancia queda ~10% de luz
+    time: f32,
+}
+
+impl Flashlight {
+    pub fn new() -> Self {
+        Self {
+            on: true,
+            battery: 1.0,
+            inner_cos: 0.16_f32.cos(),
+            outer_cos: 0.42_f32.cos(),
+            range: 7.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.on = !self.on;
+    }
+
+    /// Suma carga (p. ej. una pila recogida), 0..1.
+    pub fn recharge(&mut self, amount: f32) {
+        self.battery = (self.battery + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+        if self.on {
+            self.battery = (self.battery - DRAIN_PER_SEC * dt).max(0.0);
+        } else {
+            self.recharge(RECHARGE_PER_SEC * dt);
+        }
+    }
+
+    /// Barra de batería en el HUD.
+    pub fn draw_hud(&self, d: &mut RaylibDrawHandle, x: i32, y: i32) {
+        let (bw, bh) = (60, 10);
+        let fill = (self.battery * (bw - 2) as f32) as i32;
+        let color = if self.battery < LOW_BATTERY {
+            Color::MAROON
+        } else if self.on {
+            Color::GOLD
+        } else {
+            Color::GRAY
+        };
+        d.draw_text("Linterna", x, y, 16, Color::RAYWHITE);
+        let bx = x + d.measure_text("Linterna", 16) + 8;
+        d.draw_rectangle_lines(bx, y + 3, bw, bh, Color::LIGHTGRAY);
+        d.draw_rectangle(bx + 1, y + 4, fill, bh - 2, color);
+    }
+
+    /// Intensidad actual (0..1): apagada sin batería y parpadea cuando queda poca.
+    pub fn intensity(&self) -> f32 {
+        if !self.on || self.battery <= 0.0 {
+            return 0.0;
+        }
+        if self.battery >= LOW_BATTERY {
+            return 1.0;
+        }
+        // parpadeo pseudoaleatorio, más frecuente cuanto menos batería queda
+        let k = self.battery / LOW_BATTERY;
+        let n = ((self.time * 23.0).sin() * 43758.547).fract().abs();
+        if n > 0.35 + 0.6 * k {
+            0.15
+        } else {
+            0.4 + 0.6 * k
+        }
+    }
+}
+
+/// Luz de la escena para un frame: ambiente del nivel + lámparas + linterna.
+pub struct Lighting<'m> {
+    ambient: f32,
+    panorama: bool, // el nivel tiene cielo pintado (`sky` en [config])
+    lamps: &'m LightMap,
+    beam: f32, // intensidad de la linterna (0 = apagada)
+    inner_cos: f32,
+    outer_cos: f32,
+    inv_range2: f32,
+    hw: f32,
+    hh: f32,
+    inv_proj: f32,
+}
+
+impl<'m> Lighting<'m> {
+    pub fn new(player: &Player, maze: &'m Maze, w: i32, h: i32) -> Self {
+        let hw = w as f32 * 0.5;
+        let fl = &player.flashlight;
+        let range = fl.range * maze.block_size as f32;
+        Self {
+            ambient: maze.ambient.clamp(0.0, 1.0),
+            panorama: maze.sky.is_some(),
+            lamps: &maze.lightmap,
+            beam: fl.intensity(),
+            inner_cos: fl.inner_cos,
+            outer_cos: fl.outer_cos,
+            inv_range2: 9.0 / (range * range),
+            hw,
+            hh: h as f32 * 0.5,
+            inv_proj: (player.fov * 0.5).tan() / hw,
+        }
+    }
+
+    /// `true` si la escena queda a plena luz y no hace falta sombrear.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.ambient >= 1.0
+    }
+
+    /// Lo mismo que `is_full` sin armar la luz del frame: en un nivel a plena
+    /// luz la linterna no gasta batería ni se muestra en el HUD.
+    pub fn level_is_full(maze: &Maze) -> bool {
+        maze.ambient >= 1.0
+    }
+
+    /// Luz del cielo abierto: un panorama tiene luz propia y la oscuridad del
+    /// nivel no lo apaga; el cielo liso del color de la niebla, solo la ambiente.
+    #[inline]
+    pub fn sky(&self) -> Rgb {
+        if self.panorama {
+            [1.0; 3]
+        } else {
+            [self.ambient; 3]
+        }
+    }
+
+    /// Luz RGB del píxel de pantalla (sx, sy) a profundidad `depth` (px),
+    /// que en el mundo cae en (wx, wy) (celdas).
+    #[inline]
+    pub fn at(&self, sx: f32, sy: f32, depth: f32, wx: f32, wy: f32) -> Rgb {
+        let base = self.flashlight_at(sx, sy, depth);
+        let lamp = self.lamps.at(wx, wy);
+        lamp.map(|l| (base + l).min(MAX_LIGHT))
+    }
+
+    /// Ambiente + linterna (0..1), sin las lámparas del nivel.
+    #[inline]
+    fn flashlight_at(&self, sx: f32, sy: f32, depth: f32) -> f32 {
+        if self.beam <= 0.0 || self.ambient >= 1.0 {
+            return self.ambient;
+        }
+        // dirección del píxel en espacio de cámara: (1, x, y)
+        let x = (sx - self.hw) * self.inv_proj;
+        let y = (sy - self.hh) * self.inv_proj;
+        let r2 = 1.0 + x * x + y * y;
+        let cos_t = 1.0 / r2.sqrt();
+
+        let t = ((cos_t - self.outer_cos) / (self.inner_cos - self.outer_cos)).clamp(0.0, 1.0);
+        let cone = t * t * (3.0 - 2.0 * t);
+        if cone <= 0.0 {
+            return self.ambient;
+        }
+        let d2 = depth * depth * r2;
+        let atten = 1.0 / (1.0 + d2 * self.inv_range2);
+
+        (self.ambient + self.beam * cone * atten).min(1.0)
+    }
+}
+
+#[inline]
+pub fn apply_light(mut c: Color, light: Rgb) -> Color {
+    // `as u8` satura: las lámparas pueden sobreexponer un poco
+    c.r = (c.r as f32 * light[0]) as u8;
+    c.g = (c.g as f32 * light[1]) as u8;
+    c.b = (c.b as f32 * light[2]) as u8;
+    c
+}
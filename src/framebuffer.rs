@@ -1,31 +1,81 @@
 use raylib::prelude::*;
+use std::ffi::{CString, c_void};
 
+/// Resolución interna del render 3D, independiente del tamaño de la ventana.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RenderRes {
+    R800x600,
+    R640x480,
+    R400x300,
+    R320x240, // retro, o para máquinas lentas
+}
+
+impl RenderRes {
+    pub fn size(self) -> (i32, i32) {
+        match self {
+            RenderRes::R800x600 => (800, 600),
+            RenderRes::R640x480 => (640, 480),
+            RenderRes::R400x300 => (400, 300),
+            RenderRes::R320x240 => (320, 240),
+        }
+    }
+
+    /// La siguiente en el ciclo del menú de opciones.
+    pub fn next(self) -> Self {
+        match self {
+            RenderRes::R800x600 => RenderRes::R640x480,
+            RenderRes::R640x480 => RenderRes::R400x300,
+            RenderRes::R400x300 => RenderRes::R320x240,
+            RenderRes::R320x240 => RenderRes::R800x600,
+        }
+    }
+}
+
+/// Cómo se agranda el framebuffer hasta la ventana.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Upscale {
+    Integer, // múltiplo entero: todos los píxeles del mismo tamaño, con bordes negros
+    Aspect,  // lo más grande que entre sin deformar la imagen
+}
+
+impl Upscale {
+    pub fn next(self) -> Self {
+        match self {
+            Upscale::Integer => Upscale::Aspect,
+            Upscale::Aspect => Upscale::Integer,
+        }
+    }
+}
+
+/// Framebuffer en CPU: un `Vec<Color>` plano (fila por fila) que se sube a una
+/// única `Texture2D` con `update_texture` en cada frame.
 pub struct FrameBuffer {
     pub width: i32,
     pub height: i32,
-    pub color_buffer: Image,
+    pub pixels: Vec<Color>,
     background_color: Color,
     current_color: Color,
 }
 
 impl FrameBuffer {
     pub fn new(width: i32, height: i32, background_color: Color) -> Self {
-        let color_buffer = Image::gen_image_color(width, height, background_color);
+        let len = (width.max(0) * height.max(0)) as usize;
         FrameBuffer {
             width,
             height,
-            color_buffer,
+            pixels: vec![background_color; len],
             background_color,
             current_color: Color::WHITE,
         }
     }
 
     pub fn set_background_color(&mut self, color: Color) {
-        self.color_buffer = Image::gen_image_color(self.width, self.height, color);
+        self.background_color = color;
+        self.pixels.fill(color);
     }
 
     pub fn clear(&mut self) {
-        self.color_buffer = Image::gen_image_color(self.width, self.height, self.current_color);
+        self.pixels.fill(self.current_color);
     }
 
     pub fn set_color(&mut self, color: Color) {
@@ -33,13 +83,90 @@ impl FrameBuffer {
     }
 
     pub fn set_pixel(&mut self, x: i32, y: i32) {
+        self.put_pixel(x, y, self.current_color);
+    }
+
+    /// Escritura con color explícito y chequeo de límites.
+    #[inline]
+    pub fn put_pixel(&mut self, x: i32, y: i32, color: Color) {
         if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
-            self.color_buffer.draw_pixel(x, y, self.current_color);
+            self.pixels[(y * self.width + x) as usize] = color;
+        }
+    }
+
+    /// Escritura sin chequeo de límites (camino rápido del render 3D).
+    ///
+    /// # Safety
+    /// `0 <= x < width` y `0 <= y < height`.
+    #[inline]
+    pub unsafe fn put_pixel_unchecked(&mut self, x: i32, y: i32, color: Color) {
+        debug_assert!((0..self.width).contains(&x) && (0..self.height).contains(&y));
+        let idx = (y * self.width + x) as usize;
+        unsafe {
+            *self.pixels.get_unchecked_mut(idx) = color;
         }
     }
 
+    /// Vista RGBA8 del buffer, tal como la espera `update_texture`.
+    pub fn as_bytes(&self) -> &[u8] {
+        // `Color` es #[repr(C)] con 4 u8 (r, g, b, a): mismo layout que RGBA8.
+        unsafe {
+            std::slice::from_raw_parts(self.pixels.as_ptr() as *const u8, self.pixels.len() * 4)
+        }
+    }
+
+    /// Crea la textura de GPU (una sola vez) con el tamaño del framebuffer.
+    pub fn create_texture(
+        &self,
+        rl: &mut RaylibHandle,
+        thread: &RaylibThread,
+    ) -> Result<Texture2D, String> {
+        let img = Image::gen_image_color(self.width, self.height, self.background_color);
+        rl.load_texture_from_image(thread, &img)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Sube los píxeles a una textura existente (sin reasignar memoria en GPU).
+    pub fn upload(&self, tex: &mut Texture2D) -> Result<(), String> {
+        tex.update_texture(self.as_bytes()).map_err(|e| e.to_string())
+    }
+
+    /// Rectángulo (centrado) donde se dibuja el framebuffer en una ventana de
+    /// `screen_w` x `screen_h`.
+    pub fn fit_rect(&self, screen_w: i32, screen_h: i32, mode: Upscale) -> Rectangle {
+        let (w, h) = (self.width.max(1) as f32, self.height.max(1) as f32);
+        let (sw, sh) = (screen_w as f32, screen_h as f32);
+        let fit = (sw / w).min(sh / h);
+        let scale = match mode {
+            // si la ventana es más chica que el framebuffer no hay múltiplo entero
+            Upscale::Integer if fit >= 1.0 => fit.floor(),
+            _ => fit,
+        };
+        Rectangle {
+            x: ((sw - w * scale) / 2.0).floor(),
+            y: ((sh - h * scale) / 2.0).floor(),
+            width: w * scale,
+            height: h * scale,
+        }
+    }
+
+    /// Guarda el framebuffer como imagen (el formato sale de la extensión).
     pub fn render_to_file(&self, file_path: &str) -> Result<(), String> {
-        self.color_buffer.export_image(file_path);
-        Ok(())
+        let path = CString::new(file_path).map_err(|e| e.to_string())?;
+        // imagen de raylib que apunta a nuestros píxeles, sin copiarlos
+        let img = raylib::ffi::Image {
+            data: self.as_bytes().as_ptr() as *mut c_void,
+            width: self.width,
+            height: self.height,
+            mipmaps: 1,
+            format: PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8 as i32,
+        };
+        // SAFETY: `ExportImage` solo lee `data` (width * height * 4 bytes, que
+        // es lo que mide `pixels`) y no la libera.
+        if unsafe { raylib::ffi::ExportImage(img, path.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(format!("no se pudo escribir {}", file_path))
+        }
     }
 }
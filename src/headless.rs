@@ -0,0 +1,286 @@
+use crate::caster::{RayTrace, cast_ray};
+use crate::enemy::Enemy;
+use crate::framebuffer::FrameBuffer;
+use crate::manifest::MANIFEST_PATH;
+use crate::maze::Maze;
+use crate::player::Player;
+use crate::render3d::render3d;
+use crate::sprites::render_sprites;
+use crate::textures::{Filter, Textures};
+use raylib::prelude::*;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_3};
+
+const BLOCK_SIZE: i32 = 48; // los mismos que usa el juego en `main`
+const FOV: f32 = FRAC_PI_3;
+
+/// Una captura sin ventana ni audio: nivel, pose de la cámara y, si se
+/// quiere, el enemigo. Posiciones en celdas.
+pub struct Shot {
+    pub level: String,
+    pub pos: Option<Vector2>,   // None = el spawn 'P' del nivel
+    pub angle: Option<f32>,     // rad; None = hacia el pasillo más largo
+    pub pitch: f32,             // rad
+    pub enemy: Option<Vector2>, // mira siempre hacia la cámara
+    pub width: i32,
+    pub height: i32,
+    pub filter: Filter,
+}
+
+impl Shot {
+    pub fn new(level: &str) -> Self {
+        Self {
+            level: level.to_string(),
+            pos: None,
+            angle: None,
+            pitch: 0.0,
+            enemy: None,
+            width: 800,
+            height: 600,
+            filter: Filter::Nearest,
+        }
+    }
+}
+
+/// Dibuja la escena 3D y los sprites de la captura, igual que un frame del
+/// juego pero sin HUD ni post-proceso.
+pub fn render_shot(shot: &Shot, textures: &Textures) -> Result<FrameBuffer, String> {
+    let mut maze = Maze::load_from_file(&shot.level, BLOCK_SIZE)?;
+    let bs = maze.block_size as f32;
+    let mut player = Player::from_maze(&mut maze, 0.0, FOV);
+    if let Some(p) = shot.pos {
+        player.pos = p * bs;
+    }
+    player.a = shot.angle.unwrap_or_else(|| open_heading(&maze, &player).0);
+    player.pitch = shot.pitch;
+    let enemy = shot.enemy.map(|e| Enemy::at(e * bs, player.pos));
+
+    let mut framebuffer = FrameBuffer::new(shot.width, shot.height, Color::BLACK);
+    let z = render3d(&mut framebuffer, &mut maze, &player, textures, shot.filter);
+    let enemy_sprite = enemy.map(|e| e.sprite(textures));
+    render_sprites(
+        &mut framebuffer,
+        &maze,
+        &player,
+        &z,
+        textures,
+        &maze.props,
+        enemy_sprite.as_slice(),
+    );
+    Ok(framebuffer)
+}
+
+/// De las cuatro direcciones cardinales, la que tiene más pasillo libre
+/// delante del jugador: (ángulo, celdas hasta el muro).
+pub fn open_heading(maze: &Maze, player: &Player) -> (f32, f32) {
+    let bs = maze.block_size as f32;
+    (0..4)
+        .map(|k| {
+            let angle = k as f32 * FRAC_PI_2;
+            let hit = cast_ray(maze, player, angle, &mut RayTrace::default());
+            (angle, hit.distance / bs)
+        })
+        .fold((0.0, 0.0), |best, c| if c.1 > best.1 { c } else { best })
+}
+
+/// `--shot <nivel> <salida.png> [--pos x,y] [--angle grados] [--pitch grados]
+/// [--enemy x,y] [--size WxH] [--filter nearest|bilinear|mipmaps]`
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = "uso: --shot <nivel> <salida.png> [--pos x,y] [--angle grados] \
+                 [--pitch grados] [--enemy x,y] [--size WxH] [--filter nearest|bilinear|mipmaps]";
+    let [level, out, options @ ..] = args else {
+        return Err(usage.into());
+    };
+    let mut shot = Shot::new(level);
+    for pair in options.chunks(2) {
+        let [key, value] = pair else {
+            return Err(format!("falta el valor de '{}'\n{}", pair[0], usage));
+        };
+        match key.as_str() {
+            "--pos" => shot.pos = Some(parse_pair(value, ',')?),
+            "--angle" => shot.angle = Some(parse_num(value)?.to_radians()),
+            "--pitch" => shot.pitch = parse_num(value)?.to_radians(),
+            "--enemy" => shot.enemy = Some(parse_pair(value, ',')?),
+            "--size" => {
+                let size = parse_pair(value, 'x')?;
+                (shot.width, shot.height) = (size.x as i32, size.y as i32);
+            }
+            "--filter" => {
+                shot.filter = match value.as_str() {
+                    "nearest" => Filter::Nearest,
+                    "bilinear" => Filter::Bilinear,
+                    "mipmaps" => Filter::Mipmapped,
+                    _ => return Err(format!("filtro desconocido: '{}'", value)),
+                }
+            }
+            _ => return Err(format!("opción desconocida: '{}'\n{}", key, usage)),
+        }
+    }
+    if shot.width <= 0 || shot.height <= 0 {
+        return Err("el tamaño debe ser positivo".into());
+    }
+
+    let (textures, problems) = Textures::load_manifest(MANIFEST_PATH);
+    for p in &problems {
+        eprintln!("[texturas] {}", p);
+    }
+    let maze = Maze::load_from_file(&shot.level, BLOCK_SIZE)?;
+    for p in maze.unknown_textures(&textures) {
+        eprintln!("[niveles] {}", p);
+    }
+    render_shot(&shot, &textures)?.render_to_file(out)
+}
+
+fn parse_num(s: &str) -> Result<f32, String> {
+    s.trim()
+        .parse()
+        .map_err(|_| format!("número inválido: '{}'", s))
+}
+
+fn parse_pair(s: &str, sep: char) -> Result<Vector2, String> {
+    let (a, b) = s
+        .split_once(sep)
+        .ok_or_else(|| format!("se esperaba <a>{}<b>: '{}'", sep, s))?;
+    Ok(Vector2::new(parse_num(a)?, parse_num(b)?))
+}
+
+// Golden images: cada nivel de `levels/` se dibuja desde su spawn, mirando
+// al pasillo más largo y con el enemigo unas celdas adelante, y se compara
+// con `tests/golden/<nivel>.png`. Tras un cambio visual intencional se
+// regeneran con `UPDATE_GOLDEN=1 cargo test golden`.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    const GOLDEN_DIR: &str = "tests/golden";
+    const FAILED_DIR: &str = "target/golden"; // lo que salió, para comparar a mano
+    const CHANNEL_TOLERANCE: i32 = 3; // diferencia por canal que no cuenta
+    const MAX_BAD_FRACTION: f32 = 0.002; // píxeles distintos permitidos
+
+    fn level_shot(path: &Path) -> Shot {
+        let level = path.to_str().expect("ruta UTF-8");
+        let mut maze = Maze::load_from_file(level, BLOCK_SIZE).expect(level);
+        let player = Player::from_maze(&mut maze, 0.0, FOV);
+        let (angle, free) = open_heading(&maze, &player);
+
+        let mut shot = Shot::new(level);
+        shot.width = 320;
+        shot.height = 240;
+        shot.angle = Some(angle);
+        let ahead = (free - 0.7).clamp(0.0, 5.0);
+        let dir = Vector2::new(angle.cos(), angle.sin());
+        shot.enemy = Some(player.pos / maze.block_size as f32 + dir * ahead);
+        shot
+    }
+
+    /// Fracción de píxeles que difieren más que la tolerancia en algún canal.
+    fn bad_fraction(a: &[Color], b: &[Color]) -> f32 {
+        let bad = a
+            .iter()
+            .zip(b)
+            .filter(|(p, q)| {
+                let d = |x: u8, y: u8| (x as i32 - y as i32).abs() > CHANNEL_TOLERANCE;
+                d(p.r, q.r) || d(p.g, q.g) || d(p.b, q.b)
+            })
+            .count();
+        bad as f32 / a.len().max(1) as f32
+    }
+
+    // El render 3D reparte columnas y bandas de filas entre los hilos de
+    // rayon: con un solo hilo (en serie) tiene que salir el mismo frame.
+    #[test]
+    fn parallel_render_matches_serial() {
+        let (textures, _) = Textures::load_manifest(MANIFEST_PATH);
+        let serial_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        for path in level_paths() {
+            let shot = level_shot(&path);
+            let parallel = render_shot(&shot, &textures).expect("render");
+            let serial = serial_pool
+                .install(|| render_shot(&shot, &textures))
+                .expect("render");
+            assert!(
+                parallel.pixels == serial.pixels,
+                "{}: el render en paralelo difiere del serial",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn levels_use_declared_textures() {
+        let (textures, _) = Textures::load_manifest(MANIFEST_PATH);
+        for path in level_paths() {
+            let level = path.to_str().expect("ruta UTF-8");
+            let maze = Maze::load_from_file(level, BLOCK_SIZE).expect(level);
+            let unknown = maze.unknown_textures(&textures);
+            assert!(unknown.is_empty(), "{}:\n{}", level, unknown.join("\n"));
+        }
+    }
+
+    fn level_paths() -> Vec<std::path::PathBuf> {
+        let mut levels: Vec<_> = fs::read_dir("levels")
+            .expect("levels/")
+            .map(|e| e.expect("levels/").path())
+            .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+            .collect();
+        levels.sort();
+        assert!(!levels.is_empty(), "no hay niveles en levels/");
+        levels
+    }
+
+    #[test]
+    fn golden_levels() {
+        let (textures, problems) = Textures::load_manifest(MANIFEST_PATH);
+        assert!(
+            problems.is_empty(),
+            "{}:\n{}",
+            MANIFEST_PATH,
+            problems.join("\n")
+        );
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+        let levels = level_paths();
+
+        let mut failures = Vec::new();
+        for path in &levels {
+            let name = path.file_stem().unwrap().to_string_lossy();
+            let shot = level_shot(path);
+            let frame = render_shot(&shot, &textures).expect("render");
+            let golden = format!("{}/{}.png", GOLDEN_DIR, name);
+
+            if update {
+                fs::create_dir_all(GOLDEN_DIR).unwrap();
+                frame.render_to_file(&golden).unwrap();
+                continue;
+            }
+            let Ok(img) = Image::load_image(&golden) else {
+                failures.push(format!("{}: falta {}", name, golden));
+                continue;
+            };
+            let diff = if (img.width, img.height) != (frame.width, frame.height) {
+                1.0
+            } else {
+                bad_fraction(&frame.pixels, &img.get_image_data())
+            };
+            if diff > MAX_BAD_FRACTION {
+                fs::create_dir_all(FAILED_DIR).unwrap();
+                let actual = format!("{}/{}.png", FAILED_DIR, name);
+                frame.render_to_file(&actual).unwrap();
+                failures.push(format!(
+                    "{}: {:.2}% de píxeles distintos (ver {})",
+                    name,
+                    diff * 100.0,
+                    actual
+                ));
+            }
+        }
+        assert!(
+            failures.is_empty(),
+            "golden images distintas (UPDATE_GOLDEN=1 para regenerar):\n{}",
+            failures.join("\n")
+        );
+    }
+}
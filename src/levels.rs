@@ -33,6 +33,8 @@ impl Levels {
     pub fn set_current(&mut self, idx: usize, player: &mut Player) {
         assert!(idx < self.maps.len());
         self.current = idx;
+        // empezar o reintentar el nivel: el mapa vuelve a estar sin explorar
+        self.active_mut().reset_exploration();
         place_player_at_spawn(player, self.active_mut());
     }
 
@@ -60,6 +62,7 @@ impl Levels {
         let next = self.current + 1;
         assert!(next < self.maps.len());
         self.current = next;
+        self.active_mut().reset_exploration();
         place_player_at_spawn(player, self.active_mut());
 
         let bump = 6.0;
@@ -85,4 +88,5 @@ fn place_player_at_spawn(player: &mut Player, maze: &mut Maze) {
     let bs = maze.block_size as f32;
     player.pos.x = (pi as f32 + 0.5) * bs;
     player.pos.y = (pj as f32 + 0.5) * bs;
+    player.pitch = 0.0; // arranca mirando al frente
 }
@@ -0,0 +1,306 @@
+/// Luz RGB (1.0 = intensidad normal por canal).
+pub type Rgb = [f32; 3];
+
+/// Subdivisiones por celda del lightmap (vértices cada 1/SUB de celda).
+const SUB: usize = 4;
+
+/// Cómo se anima una luz con el tiempo.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LightMode {
+    Steady,  // fija
+    Flicker, // tubo fluorescente: tiembla todo el tiempo
+    Fail,    // casi siempre encendida, pero se corta de a ratos
+    Pulse,   // latido lento (luz de emergencia)
+}
+
+impl LightMode {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "steady" => Ok(LightMode::Steady),
+            "flicker" => Ok(LightMode::Flicker),
+            "fail" => Ok(LightMode::Fail),
+            "pulse" => Ok(LightMode::Pulse),
+            _ => Err(format!("modo de luz desconocido: '{}'", s)),
+        }
+    }
+}
+
+/// Luz puntual del nivel (posición y radio en celdas).
+#[derive(Clone, Debug)]
+pub struct PointLight {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub intensity: f32,
+    pub color: Rgb,
+    pub mode: LightMode,
+}
+
+impl PointLight {
+    /// Parsea `x y radio intensidad color [modo]`, p. ej. `5.5 3.5 4 0.9 ffd8a0 flicker`.
+    pub fn parse(line: &str) -> Result<Option<Self>, String> {
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 5 || parts.len() > 6 {
+            return Err(format!(
+                "luz inválida (x y radio intensidad color [modo]): '{}'",
+                line
+            ));
+        }
+        // `inf` y `NaN` parsean como f32 pero colgarían el horneado
+        let num = |s: &str| {
+            s.parse::<f32>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| format!("número inválido en [lights]: '{}'", s))
+        };
+        let radius = num(parts[2])?;
+        if radius <= 0.0 {
+            return Err(format!("el radio de la luz debe ser > 0: '{}'", line));
+        }
+        Ok(Some(Self {
+            x: num(parts[0])?,
+            y: num(parts[1])?,
+            radius,
+            intensity: num(parts[3])?.max(0.0),
+            color: parse_hex_color(parts[4])?,
+            mode: match parts.get(5) {
+                Some(m) => LightMode::parse(m)?,
+                None => LightMode::Steady,
+            },
+        }))
+    }
+
+    /// Factor de intensidad (0..1) en el instante `t` (segundos).
+    fn animate(&self, t: f32) -> f32 {
+        // semilla distinta por luz para que no parpadeen sincronizadas
+        let seed = self.x * 12.9898 + self.y * 78.233;
+        match self.mode {
+            LightMode::Steady => 1.0,
+            LightMode::Flicker => {
+                let n = hash01((t * 18.0).floor() + seed);
+                if n < 0.08 {
+                    0.2 // caída breve
+                } else {
+                    0.8 + 0.2 * hash01((t * 40.0).floor() + seed * 0.5)
+                }
+            }
+            LightMode::Fail => {
+                // ventanas de ~1.5 s: en algunas la luz se corta y tartamudea al volver
+                let slot = (t / 1.5).floor();
+                if hash01(slot + seed) < 0.3 {
+                    let k = (t / 1.5).fract();
+                    if k > 0.75 && hash01((t * 25.0).floor() + seed) > 0.5 {
+                        0.9
+                    } else {
+                        0.0
+                    }
+                } else {
+                    1.0
+                }
+            }
+            LightMode::Pulse => 0.6 + 0.4 * (t * 2.5 + seed).sin(),
+        }
+    }
+}
+
+fn parse_hex_color(s: &str) -> Result<Rgb, String> {
+    let hex = s.trim_start_matches('#');
+    let v = u32::from_str_radix(hex, 16)
+        .ok()
+        .filter(|_| hex.len() == 6)
+        .ok_or_else(|| format!("color inválido (se espera rrggbb): '{}'", s))?;
+    Ok([
+        ((v >> 16) & 0xff) as f32 / 255.0,
+        ((v >> 8) & 0xff) as f32 / 255.0,
+        (v & 0xff) as f32 / 255.0,
+    ])
+}
+
+#[inline]
+fn hash01(n: f32) -> f32 {
+    (n.sin() * 43758.547).fract().abs()
+}
+
+#[inline]
+fn is_solid(grid: &[Vec<char>], i: i32, j: i32) -> bool {
+    if i < 0 || j < 0 {
+        return true;
+    }
+    matches!(
+        grid.get(j as usize).and_then(|r| r.get(i as usize)),
+        Some('#' | 'A' | 'B') | None
+    )
+}
+
+/// `true` si nada sólido se interpone entre la luz y el punto (en celdas).
+/// Las puertas no cuentan: el lightmap es estático.
+fn reaches(grid: &[Vec<char>], lx: f32, ly: f32, x: f32, y: f32) -> bool {
+    let (dx, dy) = (x - lx, y - ly);
+    let len = (dx * dx + dy * dy).sqrt();
+    let steps = (len / 0.1).ceil() as i32;
+    for s in 1..steps {
+        let t = s as f32 / steps as f32;
+        if is_solid(
+            grid,
+            (lx + dx * t).floor() as i32,
+            (ly + dy * t).floor() as i32,
+        ) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Aporte precalculado de una luz sobre los vértices de su caja.
+struct BakedLight {
+    light: PointLight,
+    x0: usize,
+    y0: usize,
+    w: usize,
+    values: Vec<f32>, // caída * visibilidad, fila por fila dentro de la caja
+}
+
+/// Lightmap por sub-celda: se hornea al cargar el nivel y en cada frame
+/// se recombina con la animación de cada luz.
+pub struct LightMap {
+    w: usize, // vértices por fila = ancho * SUB + 1
+    h: usize,
+    baked: Vec<BakedLight>,
+    current: Vec<Rgb>,
+    time: f32,
+}
+
+impl LightMap {
+    pub fn bake(grid: &[Vec<char>], lights: Vec<PointLight>) -> Self {
+        let height = grid.len();
+        let width = grid.first().map_or(0, |r| r.len());
+        let w = width * SUB + 1;
+        let h = height * SUB + 1;
+        let inv = 1.0 / SUB as f32;
+
+        let baked = lights
+            .into_iter()
+            .map(|light| {
+                let vx = |x: f32| ((x * SUB as f32).max(0.0) as usize).min(w - 1);
+                let vy = |y: f32| ((y * SUB as f32).max(0.0) as usize).min(h - 1);
+                let (x0, x1) = (vx(light.x - light.radius), vx(light.x + light.radius + 1.0));
+                let (y0, y1) = (vy(light.y - light.radius), vy(light.y + light.radius + 1.0));
+                let bw = x1 - x0 + 1;
+
+                let mut values = Vec::with_capacity(bw * (y1 - y0 + 1));
+                for gy in y0..=y1 {
+                    for gx in x0..=x1 {
+                        let (x, y) = (gx as f32 * inv, gy as f32 * inv);
+                        let (dx, dy) = (x - light.x, y - light.y);
+                        let d = (dx * dx + dy * dy).sqrt() / light.radius;
+                        if d >= 1.0 {
+                            values.push(0.0);
+                            continue;
+                        }
+                        // el vértice se evalúa un poco hacia la luz: así las caras
+                        // de muro que la miran quedan iluminadas y las de atrás no
+                        let nudge = 0.02 / (d * light.radius).max(1e-3);
+                        let (tx, ty) = (x - dx * nudge, y - dy * nudge);
+                        let lit = !is_solid(grid, tx.floor() as i32, ty.floor() as i32)
+                            && reaches(grid, light.x, light.y, tx, ty);
+                        let falloff = (1.0 - d * d) * (1.0 - d * d);
+                        values.push(if lit { falloff } else { 0.0 });
+                    }
+                }
+                BakedLight {
+                    light,
+                    x0,
+                    y0,
+                    w: bw,
+                    values,
+                }
+            })
+            .collect();
+
+        let mut map = Self {
+            w,
+            h,
+            baked,
+            current: vec![[0.0; 3]; w * h],
+            time: 0.0,
+        };
+        map.update(0.0);
+        map
+    }
+
+    /// Avanza la animación y recompone el lightmap.
+    pub fn update(&mut self, dt: f32) {
+        if self.baked.is_empty() {
+            return;
+        }
+        self.time += dt;
+        self.current.fill([0.0; 3]);
+        for b in &self.baked {
+            let k = b.light.intensity * b.light.animate(self.time);
+            if k <= 0.0 {
+                continue;
+            }
+            let c = b.light.color.map(|ch| ch * k);
+            for (row, vals) in b.values.chunks(b.w).enumerate() {
+                let base = (b.y0 + row) * self.w + b.x0;
+                for (dst, &v) in self.current[base..base + b.w].iter_mut().zip(vals) {
+                    dst[0] += c[0] * v;
+                    dst[1] += c[1] * v;
+                    dst[2] += c[2] * v;
+                }
+            }
+        }
+    }
+
+    /// Luz de las lámparas en el punto (x, y) del mundo, en celdas (bilineal).
+    #[inline]
+    pub fn at(&self, x: f32, y: f32) -> Rgb {
+        if self.baked.is_empty() {
+            return [0.0; 3];
+        }
+        let fx = (x * SUB as f32).clamp(0.0, (self.w - 1) as f32);
+        let fy = (y * SUB as f32).clamp(0.0, (self.h - 1) as f32);
+        let (ix, iy) = ((fx as usize).min(self.w - 2), (fy as usize).min(self.h - 2));
+        let (tx, ty) = (fx - ix as f32, fy - iy as f32);
+
+        let i = iy * self.w + ix;
+        let (a, b) = (self.current[i], self.current[i + 1]);
+        let (c, d) = (self.current[i + self.w], self.current[i + self.w + 1]);
+        std::array::from_fn(|k| {
+            let top = a[k] + (b[k] - a[k]) * tx;
+            let bot = c[k] + (d[k] - c[k]) * tx;
+            top + (bot - top) * ty
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_light() {
+        let light = PointLight::parse("5.5 3.5 4 0.9 ffd8a0 flicker ; lámpara")
+            .unwrap()
+            .unwrap();
+        assert_eq!((light.x, light.y, light.radius), (5.5, 3.5, 4.0));
+        assert_eq!(light.mode, LightMode::Flicker);
+    }
+
+    #[test]
+    fn rejects_non_finite_values() {
+        for line in [
+            "5.5 3.5 inf 0.9 ffd8a0",
+            "5.5 3.5 NaN 0.9 ffd8a0",
+            "inf 3.5 4 0.9 ffd8a0",
+            "5.5 -inf 4 0.9 ffd8a0",
+            "5.5 3.5 4 nan ffd8a0",
+        ] {
+            assert!(PointLight::parse(line).is_err(), "aceptó '{}'", line);
+        }
+    }
+}
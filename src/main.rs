@@ -1,39 +1,73 @@
 mod audio;
+mod camera;
 mod caster;
 mod controller;
+mod decal;
+mod door;
 mod draw_utils;
 mod enemy;
+mod flashlight;
 mod framebuffer;
+mod headless;
 mod levels;
+mod lightmap;
+mod manifest;
 mod maze;
 mod menu;
 mod player;
+mod postfx;
+mod render2d;
 mod render3d;
+mod settings;
+mod sprites;
 mod textures;
 
 use audio::AudioAssets;
-use controller::process_input;
+use camera::{CameraFx, ViewOffset};
+use controller::{map_toggle_pressed, process_input};
 use draw_utils::draw_centered_text;
 use enemy::Enemy;
+use flashlight::Lighting;
 use framebuffer::FrameBuffer;
 use levels::{Levels, Transition};
+use manifest::MANIFEST_PATH;
 use menu::{Menu, MenuOutcome};
 use player::Player;
+use postfx::PostFx;
 use raylib::core::audio::RaylibAudio;
 use raylib::prelude::*;
+use render2d::MapOverlay;
 use render3d::render3d;
+use settings::Settings;
+use sprites::render_sprites;
 use textures::Textures;
 
+// al atraparte se ve la escena sacudiéndose un rato antes del game over
+const DEATH_SHAKE_SECS: f32 = 0.8;
+
 enum AppState {
     Menu,
     Playing,
 }
 
 fn main() {
+    // `--shot <nivel> <salida.png> ...`: una captura a PNG sin ventana ni audio
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|a| a == "--shot") {
+        if let Err(e) = headless::run(&args[1..]) {
+            eprintln!("[shot] {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let (mut rl, thread) = raylib::init()
         .size(800, 600)
         .title("Escape Reputation")
+        .resizable()
         .build();
+    // el menú está maquetado para 800x600: la ventana solo crece
+    rl.set_window_min_size(800, 600);
 
     rl.set_exit_key(Some(KeyboardKey::KEY_ESCAPE));
     rl.enable_cursor();
@@ -49,11 +83,21 @@ fn main() {
     audio.set_master_volume(0.85);
     let mut sfx = AudioAssets::new(&audio).expect("load audio");
 
-    // Framebuffer para el render 3D
-    let mut framebuffer = FrameBuffer::new(800, 600, Color::BLACK);
-    let mut tex = rl
-        .load_texture_from_image(&thread, &framebuffer.color_buffer)
-        .unwrap();
+    let mut settings = Settings::new();
+
+    // Framebuffer para el render 3D, a la resolución interna (se recrea si
+    // cambia en opciones) y escalado a la ventana al dibujar
+    let (rw, rh) = settings.render_res.size();
+    let mut framebuffer = FrameBuffer::new(rw, rh, Color::BLACK);
+    let mut tex = framebuffer
+        .create_texture(&mut rl, &thread)
+        .expect("framebuffer texture");
+    let mut map_overlay = MapOverlay::new(&mut rl, &thread).expect("map textures");
+
+    let (textures, problems) = Textures::load_manifest(MANIFEST_PATH);
+    for p in &problems {
+        eprintln!("[texturas] {}", p);
+    }
 
     // Carga niveles
     let maps = vec![
@@ -63,6 +107,12 @@ fn main() {
         maze::Maze::load_from_file("levels/l3.txt", 48).expect("no l3"),
         maze::Maze::load_from_file("levels/l4.txt", 48).expect("no l4"),
     ];
+    // claves que los niveles usan y el manifiesto no tiene: se ven con el damero
+    for (k, maze) in maps.iter().enumerate() {
+        for p in maze.unknown_textures(&textures) {
+            eprintln!("[niveles] l{}: {}", k, p);
+        }
+    }
     let mut levels = Levels::new(maps);
     let total_levels = levels.total_levels();
 
@@ -83,17 +133,21 @@ fn main() {
 
     // Estado de juego y enemigos
     let mut state = AppState::Menu;
-    let textures = Textures::load_default();
     let game_over_tex: Option<Texture2D> = rl.load_texture(&thread, "assets/game_over.png").ok();
     let win_tex: Option<Texture2D> = rl.load_texture(&thread, "assets/victoria.png").ok();
     let mut enemy: Option<Enemy> = None;
     let mut won = false;
     let mut dead = false;
+    let mut death_shake = 0.0f32; // segundos de sacudida que quedan antes del game over
 
     // SFX pasos
     let mut last_player_pos = player.pos;
     let mut step_accum = 0.0f32;
     let mut step_cooldown = 0.0f32;
+    let mut camera_fx = CameraFx::new();
+    let mut postfx = PostFx::new();
+    // aviso de fin de nivel (texto, segundos que le quedan en pantalla)
+    let mut level_note: Option<(String, f32)> = None;
 
     while !rl.window_should_close() {
         let dt = rl.get_frame_time();
@@ -104,17 +158,35 @@ fn main() {
         let r_pressed = rl.is_key_pressed(KeyboardKey::KEY_R);
         let m_pressed = rl.is_key_pressed(KeyboardKey::KEY_M); // <- para volver al menú
         let back_pressed = rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE);
+        let fullscreen_pressed = rl.is_key_pressed(KeyboardKey::KEY_F11);
+        let map_pressed = map_toggle_pressed(&rl);
         // ====== UPDATE ======
         match state {
             AppState::Menu => {
                 sfx.set_music_volume(0.0);
             }
             AppState::Playing => {
+                if let Some((_, t)) = level_note.as_mut() {
+                    *t -= dt;
+                }
+                level_note = level_note.filter(|(_, t)| *t > 0.0);
+                if map_pressed {
+                    map_overlay.show_automap = !map_overlay.show_automap;
+                }
+                death_shake = (death_shake - dt).max(0.0);
                 if !won && !dead {
                     {
                         let maze = levels.active_mut();
-                        let door_toggled = process_input(&rl, &mut player, maze, dt);
-                        maze.update_doors(dt);
+                        let door_toggled = process_input(&rl, &mut player, maze, &settings, dt);
+                        maze.visit(player.pos);
+                        // las puertas no se cierran encima del jugador ni del monstruo
+                        let mut occupants = vec![player.pos];
+                        occupants.extend(enemy.as_ref().map(|e| e.pos));
+                        maze.update_doors(dt, &occupants);
+                        maze.update_lights(dt);
+                        if !Lighting::level_is_full(maze) {
+                            player.flashlight.update(dt);
+                        }
                         if door_toggled {
                             sfx.sfx_door(0.8);
                         }
@@ -123,6 +195,8 @@ fn main() {
                     if let Some(e) = enemy.as_mut() {
                         if e.update(levels.active(), &player, dt) {
                             dead = true;
+                            death_shake = DEATH_SHAKE_SECS;
+                            camera_fx.add_trauma(1.0);
                             sfx.set_music_volume(0.0);
                         }
                     }
@@ -130,6 +204,14 @@ fn main() {
                     match levels.check_transition(&player) {
                         Transition::None => {}
                         Transition::NextLevel => {
+                            let explored = levels.active().explored_fraction() * 100.0;
+                            level_note = Some((
+                                format!(
+                                    "Nivel {} completado: exploraste el {:.0}%",
+                                    levels.current, explored
+                                ),
+                                3.0,
+                            ));
                             levels.advance_to_next(&mut player);
                             sfx.sfx_door(0.8);
                             unlocked = unlocked.max(levels.current + 1);
@@ -168,6 +250,7 @@ fn main() {
                     levels.current,
                     threat_enabled,
                 );
+                postfx.update(dt, sfx.threat());
 
                 // Pasos
                 step_cooldown = (step_cooldown - dt).max(0.0);
@@ -175,11 +258,8 @@ fn main() {
                 last_player_pos = player.pos;
                 step_accum += moved;
                 let speed = if dt > 0.0 { moved / dt } else { 0.0 };
-                let step_stride = if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
-                    22.0
-                } else {
-                    28.0
-                };
+                let sprinting = player.sprinting;
+                let step_stride = if sprinting { 22.0 } else { 28.0 };
 
                 let maze_now = levels.active();
                 let bs = maze_now.block_size as f32;
@@ -197,25 +277,66 @@ fn main() {
                     step_accum = 0.0;
                     step_cooldown = 0.06;
                 }
+
+                // Cámara: balanceo con la misma zancada que los pasos + sacudida
+                let enemy_cells = enemy_pos.map(|p| p.distance_to(player.pos) / bs);
+                camera_fx.update(
+                    dt,
+                    step_accum / step_stride,
+                    speed > 10.0 && on_walkable,
+                    sprinting,
+                    enemy_cells,
+                );
+                player.view = if settings.camera_motion {
+                    camera_fx.offset()
+                } else {
+                    ViewOffset::default()
+                };
             }
         }
 
         // ====== RENDER A FRAMEBUFFER (antes de begin_drawing) ======
         let mut need_scene = matches!(state, AppState::Playing);
         if need_scene {
-            let mut z = render3d(&mut framebuffer, levels.active(), &player, &textures);
-            if let Some(e) = &mut enemy {
-                e.render_sprite3d(
-                    &mut framebuffer,
-                    levels.active(),
-                    &player,
-                    &mut z,
-                    &textures,
-                );
+            if (framebuffer.width, framebuffer.height) != settings.render_res.size() {
+                let (rw, rh) = settings.render_res.size();
+                framebuffer = FrameBuffer::new(rw, rh, Color::BLACK);
+                tex = framebuffer
+                    .create_texture(&mut rl, &thread)
+                    .expect("framebuffer texture");
+            }
+            let z = render3d(
+                &mut framebuffer,
+                levels.active_mut(),
+                &player,
+                &textures,
+                settings.texture_filter,
+            );
+            let enemy_sprite = enemy.as_ref().map(|e| e.sprite(&textures));
+            let maze = levels.active();
+            render_sprites(
+                &mut framebuffer,
+                maze,
+                &player,
+                &z,
+                &textures,
+                &maze.props,
+                enemy_sprite.as_slice(),
+            );
+            postfx.apply(&mut framebuffer, &settings.effects);
+            if let Err(e) = framebuffer.upload(&mut tex) {
+                eprintln!("[render] no se pudo subir el framebuffer: {}", e);
+            }
+
+            // el monstruo solo aparece en el mapa si lo tienes a la vista
+            let maze = levels.active();
+            let seen_enemy = enemy
+                .as_ref()
+                .filter(|e| e.visible_from(maze, player.pos))
+                .map(|e| e.pos);
+            if let Err(e) = map_overlay.render(&mut rl, &thread, maze, &player, seen_enemy) {
+                eprintln!("[render] no se pudo subir el mapa: {}", e);
             }
-            tex = rl
-                .load_texture_from_image(&thread, &framebuffer.color_buffer)
-                .unwrap();
         }
 
         // ====== DRAW ======
@@ -224,7 +345,8 @@ fn main() {
 
         match state {
             AppState::Menu => {
-                match menu.draw_and_pick(mouse_pos, click_left, back_pressed, &mut d) {
+                match menu.draw_and_pick(mouse_pos, click_left, back_pressed, &mut settings, &mut d)
+                {
                     MenuOutcome::None => {}
                     MenuOutcome::StartLevel(idx) => {
                         levels.set_current(idx, &mut player);
@@ -237,6 +359,7 @@ fn main() {
                         };
                         won = false;
                         dead = false;
+                        camera_fx.reset();
                         sfx.on_enemy_spawned(1.0);
                         state = AppState::Playing;
                         want_enter_play = true; // capturar/ocultar cursor tras cerrar el draw
@@ -245,7 +368,22 @@ fn main() {
             }
 
             AppState::Playing => {
-                d.draw_texture(&tex, 0, 0, Color::WHITE);
+                // el render va escalado; el HUD, a la resolución de la ventana
+                let dst = framebuffer.fit_rect(
+                    d.get_screen_width(),
+                    d.get_screen_height(),
+                    settings.upscale,
+                );
+                let src = Rectangle {
+                    x: 0.0,
+                    y: 0.0,
+                    width: framebuffer.width as f32,
+                    height: framebuffer.height as f32,
+                };
+                d.draw_texture_pro(&tex, src, dst, Vector2::zero(), 0.0, Color::WHITE);
+                if !won && !dead {
+                    map_overlay.draw(&mut d);
+                }
                 d.draw_fps(10, 10);
                 d.draw_text(
                     &format!("Nivel: {}", levels.current),
@@ -254,6 +392,12 @@ fn main() {
                     16,
                     Color::RAYWHITE,
                 );
+                if !Lighting::level_is_full(levels.active()) {
+                    player.flashlight.draw_hud(&mut d, 10, 50);
+                }
+                if let Some((note, _)) = &level_note {
+                    draw_centered_text(&mut d, note, 80, 22, Color::RAYWHITE);
+                }
 
                 if won {
                     // === Fondo de victoria (CONTAIN: imagen completa) ===
@@ -311,6 +455,9 @@ fn main() {
 
                     // Texto de ayuda (calcula Y antes de pasar &mut d)
                     let y = d.get_screen_height() - 44;
+                    let pct = levels.active().explored_fraction() * 100.0;
+                    let explored = format!("Exploraste el {:.0}% del piso", pct);
+                    draw_centered_text(&mut d, &explored, y - 24, 18, Color::LIGHTGRAY);
                     draw_centered_text(
                         &mut d,
                         "R: jugar de nuevo  |  M: menú",
@@ -332,6 +479,7 @@ fn main() {
                         sfx.set_music_volume(0.0);
                         won = false;
                         dead = false;
+                        camera_fx.reset();
                         state = AppState::Playing;
                         want_enter_play = true; // volver a capturar el cursor
                     } else if m_pressed {
@@ -340,7 +488,7 @@ fn main() {
                         state = AppState::Menu;
                         want_back_to_menu = true; // mostrar cursor en menú
                     }
-                } else if dead {
+                } else if dead && death_shake <= 0.0 {
                     // === Fondo Game Over (CONTAIN: muestra la imagen completa) ===
                     if let Some(tex) = &game_over_tex {
                         let sw = d.get_screen_width() as f32;
@@ -396,6 +544,9 @@ fn main() {
 
                     // Instrucciones encima de la imagen (calcula Y antes de pasar &mut d)
                     let y = d.get_screen_height() - 44;
+                    let pct = levels.active().explored_fraction() * 100.0;
+                    let explored = format!("Exploraste el {:.0}% del piso", pct);
+                    draw_centered_text(&mut d, &explored, y - 24, 18, Color::LIGHTGRAY);
                     draw_centered_text(&mut d, "R: reintentar  |  M: menú", y, 24, Color::RAYWHITE);
 
                     // Input
@@ -409,6 +560,7 @@ fn main() {
                         sfx.on_enemy_spawned(0.8);
                         sfx.set_music_volume(0.0);
                         dead = false;
+                        camera_fx.reset();
                     } else if m_pressed {
                         sfx.set_music_volume(0.0);
                         menu.goto_main();
@@ -422,6 +574,10 @@ fn main() {
         // Termina el frame antes de tocar cursor
         drop(d);
 
+        if fullscreen_pressed {
+            rl.toggle_borderless_windowed();
+        }
+
         // Aplica cambios de cursor y resetea flags
         if want_enter_play {
             rl.disable_cursor(); // captura/oculta cursor (mouse-look)
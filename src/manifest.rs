@@ -0,0 +1,224 @@
+use crate::textures::Filter;
+use std::collections::HashSet;
+
+/// Manifiesto de texturas que se carga al arrancar.
+pub const MANIFEST_PATH: &str = "assets/textures.txt";
+
+/// Una textura del manifiesto: clave, archivo y cómo cortarla y filtrarla.
+#[derive(Clone, Debug)]
+pub struct TextureEntry {
+    pub key: char,
+    pub path: String,
+    pub cols: i32, // hoja de sprites: grilla de cols x rows frames (1x1 = imagen suelta)
+    pub rows: i32,
+    pub directions: usize,      // vistas por ángulo (8 = una cada 45°)
+    pub filter: Option<Filter>, // None = el filtro elegido en opciones
+}
+
+/// Animación con nombre sobre una hoja declarada en `[textures]`.
+#[derive(Clone, Debug)]
+pub struct AnimationEntry {
+    pub key: char,
+    pub name: String,
+    pub fps: f32,
+    pub frames: Vec<usize>,
+}
+
+#[derive(Default)]
+pub struct Manifest {
+    pub textures: Vec<TextureEntry>,
+    pub animations: Vec<AnimationEntry>,
+}
+
+impl Manifest {
+    /// Parsea el texto del manifiesto. Una línea mal escrita no corta la
+    /// carga: se saltea y vuelve entre los errores, con su número de línea.
+    pub fn parse(text: &str) -> (Self, Vec<String>) {
+        let mut manifest = Self::default();
+        let mut errors = Vec::new();
+        let mut keys = HashSet::new();
+        let mut section = "";
+        for (n, raw) in text.lines().enumerate() {
+            let line = raw.split(';').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim();
+                if !matches!(section, "textures" | "animations") {
+                    errors.push(format!(
+                        "línea {}: sección desconocida [{}]",
+                        n + 1,
+                        section
+                    ));
+                }
+                continue;
+            }
+            let parsed = match section {
+                "textures" => parse_texture(line).and_then(|t| {
+                    if !keys.insert(t.key) {
+                        return Err(format!("clave '{}' repetida", t.key));
+                    }
+                    manifest.textures.push(t);
+                    Ok(())
+                }),
+                "animations" => parse_animation(line).map(|a| manifest.animations.push(a)),
+                _ => Err("línea fuera de [textures] o [animations]".to_string()),
+            };
+            if let Err(e) = parsed {
+                errors.push(format!("línea {}: {}", n + 1, e));
+            }
+        }
+        (manifest, errors)
+    }
+}
+
+/// Clave de un solo carácter.
+fn parse_key(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(format!("la clave debe ser un carácter: '{}'", s)),
+    }
+}
+
+/// `clave ruta [sheet=CxF] [dirs=N] [filter=nearest|bilinear|mipmaps]`
+fn parse_texture(line: &str) -> Result<TextureEntry, String> {
+    let mut parts = line.split_whitespace();
+    let (Some(key), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(format!(
+            "textura inválida (clave ruta [opciones]): '{}'",
+            line
+        ));
+    };
+    let mut entry = TextureEntry {
+        key: parse_key(key)?,
+        path: path.to_string(),
+        cols: 1,
+        rows: 1,
+        directions: 1,
+        filter: None,
+    };
+    for opt in parts {
+        let (name, value) = opt
+            .split_once('=')
+            .ok_or_else(|| format!("opción inválida (nombre=valor): '{}'", opt))?;
+        let bad = || format!("valor inválido en '{}'", opt);
+        match name {
+            "sheet" => {
+                let (c, r) = value.split_once('x').ok_or_else(bad)?;
+                entry.cols = c.parse().map_err(|_| bad())?;
+                entry.rows = r.parse().map_err(|_| bad())?;
+                if entry.cols < 1 || entry.rows < 1 {
+                    return Err(bad());
+                }
+            }
+            "dirs" => entry.directions = value.parse().map_err(|_| bad())?,
+            "filter" => {
+                entry.filter = Some(match value {
+                    "nearest" => Filter::Nearest,
+                    "bilinear" => Filter::Bilinear,
+                    "mipmaps" => Filter::Mipmapped,
+                    _ => return Err(bad()),
+                })
+            }
+            _ => return Err(format!("opción desconocida: '{}'", name)),
+        }
+    }
+    let frames = (entry.cols * entry.rows) as usize;
+    if entry.directions == 0 || !frames.is_multiple_of(entry.directions) {
+        return Err(format!(
+            "{} vistas no reparten los {} frames de la hoja",
+            entry.directions, frames
+        ));
+    }
+    Ok(entry)
+}
+
+/// `clave nombre fps frame frame ...`
+fn parse_animation(line: &str) -> Result<AnimationEntry, String> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let [key, name, fps, frames @ ..] = &parts[..] else {
+        return Err(format!(
+            "animación inválida (clave nombre fps frames): '{}'",
+            line
+        ));
+    };
+    if frames.is_empty() {
+        return Err(format!("animación '{}' sin frames", name));
+    }
+    Ok(AnimationEntry {
+        key: parse_key(key)?,
+        name: name.to_string(),
+        fps: fps
+            .parse()
+            .map_err(|_| format!("fps inválido en '{}': '{}'", name, fps))?,
+        frames: frames
+            .iter()
+            .map(|f| f.parse().map_err(|_| format!("frame inválido: '{}'", f)))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parsea `[textures]` + `body` y devuelve solo los errores.
+    fn errors(body: &str) -> Vec<String> {
+        Manifest::parse(&format!("[textures]\n{}", body)).1
+    }
+
+    #[test]
+    fn valid_lines_parse() {
+        let (m, errs) = Manifest::parse(
+            "; comentario\n[textures]\n# a.png ; muros\nM s.png sheet=4x6 dirs=2 filter=bilinear\n\
+             [animations]\nM walk 8.0 0 1 2\n",
+        );
+        assert!(errs.is_empty(), "{:?}", errs);
+        assert_eq!(m.textures.len(), 2);
+        let sheet = &m.textures[1];
+        assert_eq!((sheet.cols, sheet.rows, sheet.directions), (4, 6, 2));
+        assert_eq!(sheet.filter, Some(Filter::Bilinear));
+        assert_eq!(m.animations[0].frames, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn bad_key() {
+        let errs = errors("ab pared.png\n");
+        assert_eq!(errs.len(), 1);
+        assert!(errs[0].starts_with("línea 2:"), "{}", errs[0]);
+        assert!(errs[0].contains("un carácter"), "{}", errs[0]);
+    }
+
+    #[test]
+    fn duplicate_key() {
+        let (m, errs) = Manifest::parse("[textures]\n# a.png\n# b.png\n");
+        assert_eq!(m.textures.len(), 1);
+        assert_eq!(m.textures[0].path, "a.png"); // queda la primera
+        assert_eq!(errs.len(), 1);
+        assert!(errs[0].contains("repetida"), "{}", errs[0]);
+    }
+
+    #[test]
+    fn sheet_not_split_into_views() {
+        // 4x3 = 12 frames no se reparten en 5 vistas
+        let errs = errors("M hoja.png sheet=4x3 dirs=5\n");
+        assert_eq!(errs.len(), 1);
+        assert!(errs[0].contains("5 vistas"), "{}", errs[0]);
+    }
+
+    #[test]
+    fn unknown_section() {
+        let (m, errs) = Manifest::parse("[textura]\n# a.png\n[textures]\n. b.png\n");
+        // la sección mal escrita y su línea se reportan; lo demás carga igual
+        assert_eq!(errs.len(), 2, "{:?}", errs);
+        assert!(
+            errs[0].contains("sección desconocida [textura]"),
+            "{}",
+            errs[0]
+        );
+        assert_eq!(m.textures.len(), 1);
+        assert_eq!(m.textures[0].key, '.');
+    }
+}
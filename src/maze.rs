@@ -1,20 +1,44 @@
-use crate::framebuffer::FrameBuffer;
+use crate::caster::Face;
+use crate::decal::Decal;
+use crate::door::Door;
+use crate::lightmap::{LightMap, PointLight};
 use crate::player::Player;
+use crate::sprites::Sprite;
+use crate::textures::Textures;
 use raylib::prelude::*;
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-};
+use std::{collections::HashMap, fs};
 
-const AUTO_CLOSE_SECS: f32 = 1.5;
+const DOOR_CLEARANCE: f32 = 12.0; // px: radio de jugador/monstruo al ver si ocupan un vano
+
+/// Superficie fina sobre la línea media de una celda: la losa de las puertas
+/// (enmarcada por jambas) o una reja / ventana que deja ver lo de atrás.
+#[derive(Clone, Copy, Debug)]
+pub struct ThinWall {
+    pub along_y: bool,     // la losa va sobre x = i + 0.5 y corre en y
+    pub open: f32,         // fracción metida en el muro por el lado de menor coordenada
+    pub see_through: bool, // textura con alpha: el caster sigue de largo
+}
 
 pub struct Maze {
     pub grid: Vec<Vec<char>>,
     pub width: usize,
     pub height: usize,
     pub block_size: i32,
-    doors_open: HashSet<(usize, usize)>,
-    door_timers: HashMap<(usize, usize), f32>,
+    pub has_ceiling: bool, // techo texturizado ('^') en vez de cielo abierto
+    pub ambient: f32,      // luz ambiente 0..1 (1 = sin oscuridad; la linterna suma)
+    pub sky: Option<char>, // textura del panorama del cielo; None = cielo liso color niebla
+    pub fog: Color,        // color al que se funde todo a lo lejos
+    floor_layer: Option<Vec<Vec<char>>>, // textura de piso por celda
+    ceiling_layer: Option<Vec<Vec<char>>>, // textura de techo por celda ('-' = cielo)
+    pub lightmap: LightMap, // luces fijas del nivel, horneadas al cargar
+    pub props: Vec<Sprite>, // decoración billboard (sillas, camas, notas...)
+    // el monstruo te ve por encima de muros bajos y mostradores
+    pub see_over_low: bool,
+    doors: HashMap<(usize, usize), Door>,
+    see_through: HashMap<(usize, usize), bool>, // rejas y ventanas -> `along_y`
+    decals: HashMap<((i32, i32), Face), char>,  // calcomanía de cada cara de muro
+    visited: Vec<bool>, // celdas por las que pasó el jugador (y sus vecinas)
+    seen: Vec<bool>,    // celdas que recorrió algún rayo del render (niebla de guerra)
 }
 
 /*
@@ -24,16 +48,162 @@ pub struct Maze {
   'P' personaje (spawn; se limpia a '.')
   'A' muro con textura 1
   'B' muro con textura 2
-  'C' puerta (cerrada = bloquea; abierta = NO bloquea ni se dibuja)
+  'C' puerta corrediza (losa en la mitad de la celda; bloquea hasta estar casi abierta;
+      los muros a los costados del vano se ven con la textura de jamba)
+  'R' reja (pared fina transparente en la mitad de la celda; bloquea el paso, no la vista)
+  'V' ventana (igual que la reja, con vidrio semitransparente)
+  'H' muro a media altura (bloquea el paso; se ve lo que hay detrás)
+  'K' mostrador / mesada (como 'H', más bajo)
   'E' escaleras / salida (visible, NO bloquea)
   'F' final (visible, NO bloquea)
   'T' spawn del monstruo (no bloquea; visible en minimapa)
+
+  Después de la grilla (separadas por una línea vacía) pueden venir secciones
+  opcionales con el formato `[nombre]` seguido de líneas `clave = valor`:
+
+  [config]
+  ceiling = on      ; on = techo texturizado, off = cielo abierto (default)
+  ambient = 0.08    ; luz ambiente 0..1 (default 1 = todo iluminado)
+  sky = ~           ; panorama del cielo (clave de textura; gira con la vista y tiene
+                    ; luz propia); sin esto el cielo es liso, del color de la niebla
+  fog = 141828      ; color de la niebla a lo lejos (rrggbb, default 141828)
+  see_over_low = on ; el monstruo ve por encima de 'H' y 'K' (default on)
+
+  Las secciones [floor] y [ceiling] son una segunda grilla del mismo tamaño
+  que el mapa; cada carácter es la clave de textura de esa celda:
+
+  [floor]           ; '.' piso normal, 's' sangre, 'b' azulejos, 'a' alfombra
+  [ceiling]         ; '^' techo normal, '-' sin techo (cielo); implica ceiling = on
+
+  La sección [lights] tiene una luz puntual por línea (posición y radio en
+  celdas, color en hex, modo opcional: steady, flicker, fail o pulse):
+
+  [lights]
+  5.5 3.5  4  0.9  ffd8a0  flicker   ; x y radio intensidad color modo
+
+  La sección [props] coloca sprites decorativos, uno por línea (posición en
+  celdas; escala = alto en bloques; altura = elevación de la base, en bloques):
+
+  [props]
+  w 3.5 7.5 0.6     ; clave x y [escala [altura]]: 'w' silla, 'k' cama, 'n' nota
+
+  La sección [decals] pega calcomanías (texturas con alpha) sobre las caras
+  de un muro, una por cara: clave, celda del muro y caras (n s e w):
+
+  [decals]
+  h 16 12 s         ; 'h' huellas de manos, 'x' cartel de salida, 'r' número de sala
 */
 
+/// Sección opcional del archivo de nivel: nombre y líneas crudas.
+type Section<'a> = (&'a str, Vec<&'a str>);
+
+/// Separa el archivo en la grilla principal y las secciones `[nombre]`.
+fn split_sections(text: &str) -> Result<(Vec<&str>, Vec<Section<'_>>), String> {
+    let mut lines = text.lines().peekable();
+    let mut grid = Vec::new();
+    while let Some(l) = lines.peek() {
+        if l.trim().is_empty() || l.trim_start().starts_with('[') {
+            break;
+        }
+        grid.push(*l);
+        lines.next();
+    }
+
+    let mut sections: Vec<Section> = Vec::new();
+    for l in lines {
+        let t = l.trim();
+        if let Some(name) = t.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+            sections.push((name.trim(), Vec::new()));
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push(l);
+        } else if !t.is_empty() {
+            return Err(format!("línea fuera de una sección: '{}'", t));
+        }
+    }
+    Ok((grid, sections))
+}
+
+/// Parsea una línea `clave = valor` (ignora vacías y comentarios con ';').
+fn parse_key_value(line: &str) -> Result<Option<(&str, &str)>, String> {
+    let line = line.split(';').next().unwrap_or("").trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let (k, v) = line
+        .split_once('=')
+        .ok_or_else(|| format!("se esperaba 'clave = valor': '{}'", line))?;
+    Ok(Some((k.trim(), v.trim())))
+}
+
+/// Parsea una capa de texturas con las mismas dimensiones que la grilla.
+fn parse_layer(
+    name: &str,
+    body: &[&str],
+    width: usize,
+    height: usize,
+) -> Result<Vec<Vec<char>>, String> {
+    let rows: Vec<Vec<char>> = body
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.chars().collect())
+        .collect();
+    if rows.len() != height || !rows.iter().all(|r| r.len() == width) {
+        return Err(format!(
+            "la capa [{}] debe medir {}x{} como la grilla",
+            name, width, height
+        ));
+    }
+    Ok(rows)
+}
+
+#[inline]
+fn layer_cell(layer: &Option<Vec<Vec<char>>>, i: i32, j: i32) -> Option<char> {
+    let layer = layer.as_ref()?;
+    if i < 0 || j < 0 {
+        return None;
+    }
+    layer.get(j as usize)?.get(i as usize).copied()
+}
+
+fn parse_f32(key: &str, v: &str) -> Result<f32, String> {
+    v.parse::<f32>()
+        .map_err(|_| format!("valor numérico inválido para '{}': '{}'", key, v))
+}
+
+fn parse_char(key: &str, v: &str) -> Result<char, String> {
+    let mut chars = v.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(format!(
+            "se esperaba un solo carácter para '{}': '{}'",
+            key, v
+        )),
+    }
+}
+
+fn parse_color(key: &str, v: &str) -> Result<Color, String> {
+    let hex = v.trim_start_matches('#');
+    let rgb = u32::from_str_radix(hex, 16)
+        .ok()
+        .filter(|_| hex.len() == 6)
+        .ok_or_else(|| format!("color inválido para '{}' (rrggbb): '{}'", key, v))?;
+    let [_, r, g, b] = rgb.to_be_bytes();
+    Ok(Color::new(r, g, b, 255))
+}
+
+fn parse_bool(key: &str, v: &str) -> Result<bool, String> {
+    match v {
+        "on" | "true" | "si" | "sí" | "1" => Ok(true),
+        "off" | "false" | "no" | "0" => Ok(false),
+        _ => Err(format!("valor inválido para '{}': '{}'", key, v)),
+    }
+}
+
 impl Maze {
     pub fn load_from_file(path: &str, block_size: i32) -> Result<Self, String> {
         let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
-        let grid: Vec<Vec<char>> = text.lines().map(|l| l.chars().collect()).collect();
+        let (grid_lines, sections) = split_sections(&text)?;
+        let grid: Vec<Vec<char>> = grid_lines.iter().map(|l| l.chars().collect()).collect();
 
         if grid.is_empty() || grid[0].is_empty() {
             return Err("maze vacío o inválido".into());
@@ -49,7 +219,7 @@ impl Maze {
         for row in &grid {
             for &c in row {
                 match c {
-                    '#' | '.' | 'P' | 'A' | 'B' | 'C' | 'E' | 'F' | 'T' => {}
+                    '#' | '.' | 'P' | 'A' | 'B' | 'C' | 'R' | 'V' | 'H' | 'K' | 'E' | 'F' | 'T' => {}
                     _ => return Err(format!("símbolo no permitido: '{}'", c)),
                 }
                 if c == 'P' {
@@ -67,13 +237,116 @@ impl Maze {
             return Err("debe existir al menos una salida ('E' o 'F')".into());
         }
 
+        let mut has_ceiling = false;
+        let mut ambient = 1.0;
+        let mut sky = None;
+        let mut fog = Color::new(20, 24, 40, 255);
+        let mut see_over_low = true;
+        let mut floor_layer = None;
+        let mut ceiling_layer = None;
+        let mut lights = Vec::new();
+        let mut props = Vec::new();
+        let mut decals = HashMap::new();
+        for (name, body) in &sections {
+            match *name {
+                "floor" => floor_layer = Some(parse_layer(name, body, width, height)?),
+                "ceiling" => ceiling_layer = Some(parse_layer(name, body, width, height)?),
+                "props" => {
+                    for line in body {
+                        props.extend(Sprite::parse(line, block_size)?);
+                    }
+                }
+                "decals" => {
+                    for line in body {
+                        let Some(decal) = Decal::parse(line)? else {
+                            continue;
+                        };
+                        let (i, j) = decal.cell;
+                        let on_wall = grid
+                            .get(j as usize)
+                            .and_then(|row| row.get(i as usize))
+                            .is_some_and(|c| "#ABHKEFCRV".contains(*c));
+                        if !on_wall {
+                            return Err(format!("decal en ({}, {}), que no es un muro", i, j));
+                        }
+                        for face in decal.faces {
+                            decals.insert((decal.cell, face), decal.key);
+                        }
+                    }
+                }
+                "lights" => {
+                    for line in body {
+                        lights.extend(PointLight::parse(line)?);
+                    }
+                }
+                "config" => {
+                    for line in body {
+                        let Some((k, v)) = parse_key_value(line)? else {
+                            continue;
+                        };
+                        match k {
+                            "ceiling" => has_ceiling = parse_bool(k, v)?,
+                            "ambient" => ambient = parse_f32(k, v)?.clamp(0.0, 1.0),
+                            "sky" => sky = Some(parse_char(k, v)?),
+                            "fog" => fog = parse_color(k, v)?,
+                            "see_over_low" => see_over_low = parse_bool(k, v)?,
+                            _ => return Err(format!("opción desconocida en [config]: '{}'", k)),
+                        }
+                    }
+                }
+                _ => return Err(format!("sección desconocida: '[{}]'", name)),
+            }
+        }
+        // Una capa de techo implica techo aunque [config] no lo diga
+        has_ceiling |= ceiling_layer.is_some();
+        let lightmap = LightMap::bake(&grid, lights);
+
+        // Paredes finas: la losa va perpendicular al paso, o sea sobre
+        // x = i + 0.5 si la celda está abierta a este y oeste (o encerrada
+        // arriba y abajo, como una puerta en un pasillo este-oeste)
+        let solid = |i: Option<usize>, j: Option<usize>| {
+            j.and_then(|j| grid.get(j))
+                .zip(i)
+                .and_then(|(r, i)| r.get(i))
+                .is_none_or(|c| matches!(c, '#' | 'A' | 'B'))
+        };
+        let mut doors = HashMap::new();
+        let mut see_through = HashMap::new();
+        for (j, row) in grid.iter().enumerate() {
+            for (i, &c) in row.iter().enumerate() {
+                let along_y = (!solid(i.checked_sub(1), Some(j)) && !solid(Some(i + 1), Some(j)))
+                    || (solid(Some(i), j.checked_sub(1)) && solid(Some(i), Some(j + 1)));
+                match c {
+                    'C' => {
+                        doors.insert((i, j), Door::new(along_y));
+                    }
+                    'R' | 'V' => {
+                        see_through.insert((i, j), along_y);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         Ok(Self {
             grid,
             width,
             height,
             block_size,
-            doors_open: HashSet::new(),
-            door_timers: HashMap::new(),
+            has_ceiling,
+            ambient,
+            sky,
+            fog,
+            see_over_low,
+            floor_layer,
+            ceiling_layer,
+            lightmap,
+            props,
+            doors,
+            see_through,
+            decals,
+            visited: vec![false; width * height],
+            seen: vec![false; width * height],
         })
     }
 
@@ -94,23 +367,66 @@ impl Maze {
         self.cell(i as isize, j as isize)
     }
 
+    /// Clave de textura del piso en la celda (i, j).
+    #[inline]
+    pub fn floor_at(&self, i: i32, j: i32) -> char {
+        layer_cell(&self.floor_layer, i, j).unwrap_or('.')
+    }
+
+    /// Clave de textura del techo en la celda (i, j); `None` = cielo abierto.
+    #[inline]
+    pub fn ceiling_at(&self, i: i32, j: i32) -> Option<char> {
+        if !self.has_ceiling {
+            return None;
+        }
+        match layer_cell(&self.ceiling_layer, i, j) {
+            Some('-') => None,
+            Some(c) => Some(c),
+            None => Some('^'),
+        }
+    }
+
+    /// Pared fina de la celda (i, j), si la hay. El caster la corta en la
+    /// mitad de la celda en vez de tratar la celda entera como muro.
+    #[inline]
+    pub fn thin_wall_at(&self, i: i32, j: i32) -> Option<ThinWall> {
+        if let Some(door) = self.door_at(i, j) {
+            return Some(ThinWall {
+                along_y: door.along_y,
+                open: door.open,
+                see_through: false,
+            });
+        }
+        if i < 0 || j < 0 {
+            return None;
+        }
+        let &along_y = self.see_through.get(&(i as usize, j as usize))?;
+        Some(ThinWall {
+            along_y,
+            open: 0.0,
+            see_through: true,
+        })
+    }
+
     // —— Estado de puertas ——
+    /// `true` si la puerta está lo bastante abierta para pasar.
     #[inline]
     pub fn door_is_open(&self, i: usize, j: usize) -> bool {
-        self.doors_open.contains(&(i, j))
+        self.doors.get(&(i, j)).is_some_and(Door::is_passable)
     }
 
-    pub fn toggle_door_at(&mut self, i: usize, j: usize) {
-        if self.grid[j][i] != 'C' {
-            return;
+    /// Puerta de la celda (i, j), si es una 'C'.
+    #[inline]
+    pub fn door_at(&self, i: i32, j: i32) -> Option<&Door> {
+        if i < 0 || j < 0 {
+            return None;
         }
-        if !self.doors_open.remove(&(i, j)) {
-            // abrir
-            self.doors_open.insert((i, j));
-            self.door_timers.insert((i, j), AUTO_CLOSE_SECS);
-        } else {
-            // cerrar manual
-            self.door_timers.remove(&(i, j));
+        self.doors.get(&(i as usize, j as usize))
+    }
+
+    pub fn toggle_door_at(&mut self, i: usize, j: usize) {
+        if let Some(door) = self.doors.get_mut(&(i, j)) {
+            door.toggle();
         }
     }
 
@@ -172,20 +488,28 @@ impl Maze {
         self.toggle_door_near(player)
     }
 
-    pub fn update_doors(&mut self, dt: f32) {
-        let mut to_close: Vec<(usize, usize)> = Vec::new();
-        for (k, t) in self.door_timers.iter_mut() {
-            *t -= dt;
-            if *t <= 0.0 {
-                to_close.push(*k);
-            }
-        }
-        for (i, j) in to_close {
-            self.doors_open.remove(&(i, j));
-            self.door_timers.remove(&(i, j));
+    /// Anima las puertas. `occupants` son las posiciones (px) de quienes
+    /// pueden quedar en un vano (jugador y monstruo): sobre ellos no se cierra.
+    pub fn update_doors(&mut self, dt: f32, occupants: &[Vector2]) {
+        let bs = self.block_size as f32;
+        for (&(i, j), door) in self.doors.iter_mut() {
+            // vano de la puerta con un margen: el radio del cuerpo asoma antes
+            let x0 = i as f32 * bs - DOOR_CLEARANCE;
+            let y0 = j as f32 * bs - DOOR_CLEARANCE;
+            let span = bs + 2.0 * DOOR_CLEARANCE;
+            let (x1, y1) = (x0 + span, y0 + span);
+            let occupied = occupants
+                .iter()
+                .any(|p| p.x > x0 && p.x < x1 && p.y > y0 && p.y < y1);
+            door.update(dt, occupied);
         }
     }
 
+    /// Avanza el parpadeo / fallas de las luces del nivel.
+    pub fn update_lights(&mut self, dt: f32) {
+        self.lightmap.update(dt);
+    }
+
     #[inline]
     pub fn is_blocking_at(&self, i: isize, j: isize) -> bool {
         let c = self.cell(i, j);
@@ -203,13 +527,82 @@ impl Maze {
     pub fn is_surface_at(&self, i: i32, j: i32) -> bool {
         let c: char = self.tile_at(i, j);
         match c {
-            '#' | 'A' | 'B' => true,
-            'C' => !self.door_is_open(i as usize, j as usize),
-            'E' | 'F' => true, 
+            '#' | 'A' | 'B' | 'H' | 'K' => true,
+            'C' | 'R' | 'V' => false, // pared fina: la resuelve el caster con `thin_wall_at`
+            'E' | 'F' => true,
             _ => false,
         }
     }
 
+    /// Claves de textura que el nivel usa en [floor], [ceiling], [props],
+    /// [decals] y `sky` pero que el manifiesto no declara; una línea por
+    /// clave, con la primera celda (o sección) donde aparece.
+    pub fn unknown_textures(&self, textures: &Textures) -> Vec<String> {
+        let mut used: Vec<(char, String)> = Vec::new();
+        for (name, layer) in [
+            ("floor", &self.floor_layer),
+            ("ceiling", &self.ceiling_layer),
+        ] {
+            for (j, row) in layer.iter().flatten().enumerate() {
+                for (i, &c) in row.iter().enumerate() {
+                    // '-' en el techo es cielo abierto, no una textura
+                    if !(name == "ceiling" && c == '-') {
+                        used.push((c, format!("[{}] en ({}, {})", name, i, j)));
+                    }
+                }
+            }
+        }
+        used.extend(self.props.iter().map(|p| (p.key, "[props]".to_string())));
+        used.extend(self.decals.values().map(|&k| (k, "[decals]".to_string())));
+        used.extend(self.sky.map(|k| (k, "sky en [config]".to_string())));
+
+        let mut problems = Vec::new();
+        let mut reported = Vec::new();
+        for (key, place) in used {
+            if !textures.knows(key) && !reported.contains(&key) {
+                reported.push(key);
+                problems.push(format!(
+                    "textura '{}' sin declarar en el manifiesto ({})",
+                    key, place
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Clave de la calcomanía pegada en esa cara de la celda, si hay.
+    #[inline]
+    pub fn decal_at(&self, cell: (i32, i32), face: Face) -> Option<char> {
+        self.decals.get(&(cell, face)).copied()
+    }
+
+    /// Alto de la superficie de la celda, en bloques (1 = del piso al techo).
+    #[inline]
+    pub fn wall_height(&self, i: i32, j: i32) -> f32 {
+        match self.tile_at(i, j) {
+            'H' => 0.5,
+            'K' => 0.4,
+            _ => 1.0,
+        }
+    }
+
+    /// `true` si no se puede pasar por la celda: además de los muros y las
+    /// puertas cerradas, las rejas, ventanas y muros bajos.
+    #[inline]
+    pub fn blocks_movement_at(&self, i: isize, j: isize) -> bool {
+        matches!(self.cell(i, j), 'R' | 'V' | 'H' | 'K') || self.is_blocking_at(i, j)
+    }
+
+    /// `true` si la celda tapa la vista. Las rejas y ventanas no la tapan;
+    /// los muros bajos sí, salvo con `see_over_low`.
+    #[inline]
+    pub fn blocks_sight_at(&self, i: isize, j: isize, see_over_low: bool) -> bool {
+        match self.cell(i, j) {
+            'H' | 'K' => !see_over_low,
+            _ => self.is_blocking_at(i, j),
+        }
+    }
+
     pub fn find_first(&self, tile: char) -> Option<(i32, i32)> {
         for (j, row) in self.grid.iter().enumerate() {
             for (i, &c) in row.iter().enumerate() {
@@ -221,6 +614,60 @@ impl Maze {
         None
     }
 
+    /// Marca como visitadas la celda de `pos` y sus ocho vecinas.
+    pub fn visit(&mut self, pos: Vector2) {
+        let bs = self.block_size as f32;
+        let (ci, cj) = ((pos.x / bs) as i32, (pos.y / bs) as i32);
+        for j in cj - 1..=cj + 1 {
+            for i in ci - 1..=ci + 1 {
+                if let Some(k) = self.cell_index(i, j) {
+                    self.visited[k] = true;
+                }
+            }
+        }
+    }
+
+    /// Marca como vistas las celdas que recorrieron los rayos del render.
+    pub fn mark_seen(&mut self, cells: impl IntoIterator<Item = (i32, i32)>) {
+        for (i, j) in cells {
+            if let Some(k) = self.cell_index(i, j) {
+                self.seen[k] = true;
+            }
+        }
+    }
+
+    /// `true` si la celda ya aparece en el mapa: se vio o se pasó al lado
+    /// (fuera del mapa, nunca).
+    pub fn is_explored(&self, i: i32, j: i32) -> bool {
+        self.cell_index(i, j)
+            .is_some_and(|k| self.visited[k] || self.seen[k])
+    }
+
+    /// Fracción (0..1) del piso del nivel ya explorada; los muros no cuentan.
+    pub fn explored_fraction(&self) -> f32 {
+        let mut floor = 0usize;
+        let mut explored = 0usize;
+        for (k, &c) in self.grid.iter().flatten().enumerate() {
+            if !matches!(c, '#' | 'A' | 'B') {
+                floor += 1;
+                explored += (self.visited[k] || self.seen[k]) as usize;
+            }
+        }
+        explored as f32 / floor.max(1) as f32
+    }
+
+    /// Olvida lo explorado (al empezar o reintentar el nivel).
+    pub fn reset_exploration(&mut self) {
+        self.visited.fill(false);
+        self.seen.fill(false);
+    }
+
+    #[inline]
+    fn cell_index(&self, i: i32, j: i32) -> Option<usize> {
+        let inside = (0..self.width as i32).contains(&i) && (0..self.height as i32).contains(&j);
+        inside.then(|| j as usize * self.width + i as usize)
+    }
+
     // —— 2D debug/minimapa ——
     pub fn cell_color(ch: char) -> Color {
         match ch {
@@ -229,6 +676,8 @@ impl Maze {
             'A' => Color::BLUE,
             'B' => Color::MAROON,
             'C' => Color::ORANGE,
+            'R' | 'V' => Color::SKYBLUE,
+            'H' | 'K' => Color::GRAY,
             'E' => Color::LIME,
             'F' => Color::GOLD,
             'P' => Color::GOLD,
@@ -237,29 +686,3 @@ impl Maze {
         }
     }
 }
-
-// helpers 2D (opcional)
-fn draw_cell(framebuffer: &mut FrameBuffer, x0: i32, y0: i32, size: i32, color: Color) {
-    framebuffer.set_color(color);
-    for y in y0..(y0 + size) {
-        for x in x0..(x0 + size) {
-            framebuffer.set_pixel(x, y);
-        }
-    }
-}
-pub fn render_maze_2d(framebuffer: &mut FrameBuffer, maze: &Maze) {
-    framebuffer.set_color(Color::BLACK);
-    for y in 0..framebuffer.height {
-        for x in 0..framebuffer.width {
-            framebuffer.set_pixel(x, y);
-        }
-    }
-    let bs = maze.block_size;
-    for (j, row) in maze.grid.iter().enumerate() {
-        for (i, &cell) in row.iter().enumerate() {
-            let xo = (i as i32) * bs;
-            let yo = (j as i32) * bs;
-            draw_cell(framebuffer, xo, yo, bs, Maze::cell_color(cell));
-        }
-    }
-}
@@ -1,4 +1,8 @@
 use crate::draw_utils::draw_centered_text;
+use crate::framebuffer::Upscale;
+use crate::postfx::Effect;
+use crate::settings::Settings;
+use crate::textures::Filter;
 use raylib::prelude::*;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -6,6 +10,7 @@ enum Screen {
     Main,
     LevelSelect,
     HowTo,
+    Options,
 }
 
 pub enum MenuOutcome {
@@ -46,6 +51,7 @@ impl Menu {
         mouse_pos: Vector2,
         click_left: bool,
         back_pressed: bool,
+        settings: &mut Settings,
         d: &mut RaylibDrawHandle,
     ) -> MenuOutcome {
         self.draw_background(d);
@@ -53,6 +59,7 @@ impl Menu {
             Screen::Main => self.draw_main(mouse_pos, click_left, d),
             Screen::LevelSelect => self.draw_level_select(mouse_pos, click_left, back_pressed, d),
             Screen::HowTo => self.draw_howto(back_pressed, d),
+            Screen::Options => self.draw_options(mouse_pos, click_left, back_pressed, settings, d),
         }
     }
 
@@ -92,13 +99,19 @@ impl Menu {
         click_left: bool,
         d: &mut RaylibDrawHandle,
     ) -> MenuOutcome {
-        // Layout
+        // Layout: la barra se centra para que entren los cuatro botones
         let y = 18.0;
-        let mut x = 100.0;
-        let fs = 26;
-        let pad_x = 22.0;
+        let fs = 22;
+        let pad_x = 16.0;
         let pad_y = 10.0;
         let gap = 12.0;
+        let labels = ["Empezar", "Seleccionar nivel", "Cómo jugar", "Opciones"];
+        let bar_w: f32 = labels
+            .iter()
+            .map(|l| d.measure_text(l, fs) as f32 + pad_x * 2.0 + gap)
+            .sum::<f32>()
+            - gap;
+        let mut x = ((d.get_screen_width() as f32 - bar_w) / 2.0).max(10.0);
 
         // Empezar
         let (clicked, w) = pill_button(d, mouse_pos, click_left, x, y, "Empezar", fs, pad_x, pad_y);
@@ -126,7 +139,7 @@ impl Menu {
         x += w + gap;
 
         // Cómo jugar
-        let (clicked, _w) = pill_button(
+        let (clicked, w) = pill_button(
             d,
             mouse_pos,
             click_left,
@@ -140,6 +153,14 @@ impl Menu {
         if clicked {
             self.screen = Screen::HowTo;
         }
+        x += w + gap;
+
+        // Opciones
+        let (clicked, _w) =
+            pill_button(d, mouse_pos, click_left, x, y, "Opciones", fs, pad_x, pad_y);
+        if clicked {
+            self.screen = Screen::Options;
+        }
 
         MenuOutcome::None
     }
@@ -211,9 +232,10 @@ impl Menu {
         draw_centered_text(d, "Cómo jugar", 64, 30, Color::RAYWHITE);
         let lines = [
             "WASD / Flechas: mover",
-            "Mouse: girar cámara",
+            "Mouse o stick derecho: girar y mirar arriba/abajo",
             "Shift o LT/RT: correr",
             "E o Botón A: abrir/cerrar puertas",
+            "F o Botón Y: linterna (se recarga apagada)",
             "Sube de nivel por 'E'; en el último busca 'F' para escapar.",
             "Si te atrapan: pulsa R para reintentar.",
             "M: volver al menú en juego",
@@ -235,10 +257,115 @@ impl Menu {
         }
         MenuOutcome::None
     }
+
+    fn draw_options(
+        &mut self,
+        mouse_pos: Vector2,
+        click_left: bool,
+        back_pressed: bool,
+        settings: &mut Settings,
+        d: &mut RaylibDrawHandle,
+    ) -> MenuOutcome {
+        draw_centered_text(d, "Opciones", 64, 30, Color::RAYWHITE);
+
+        // filas compactas: entran las opciones y los efectos de post-proceso
+        let bw = 420.0;
+        let x = (d.get_screen_width() as f32 - bw) / 2.0;
+        let mut y = 106.0;
+        let mut row = |d: &mut RaylibDrawHandle, label: &str, value: &str| {
+            let r = rect(x, y, bw, 36.0);
+            y += 40.0;
+            option_row(d, mouse_pos, click_left, r, label, value)
+        };
+
+        if row(d, "Invertir eje Y", on_off(settings.invert_y)) {
+            settings.invert_y = !settings.invert_y;
+        }
+        if row(d, "Movimiento de cámara", on_off(settings.camera_motion)) {
+            settings.camera_motion = !settings.camera_motion;
+        }
+        let filter = filter_label(settings.texture_filter);
+        if row(d, "Filtrado de texturas", filter) {
+            settings.texture_filter = settings.texture_filter.next();
+        }
+        let (rw, rh) = settings.render_res.size();
+        if row(d, "Resolución interna", &format!("{rw}x{rh}")) {
+            settings.render_res = settings.render_res.next();
+        }
+        if row(d, "Escalado", upscale_label(settings.upscale)) {
+            settings.upscale = settings.upscale.next();
+        }
+        for effect in Effect::ALL {
+            let on = settings.effects.contains(&effect);
+            if row(d, effect.label(), on_off(on)) {
+                settings.toggle_effect(effect);
+            }
+        }
+
+        d.draw_text(
+            "BACKSPACE: regresar",
+            20,
+            d.get_screen_height() - 28,
+            18,
+            Color::LIGHTGRAY,
+        );
+        if back_pressed {
+            self.screen = Screen::Main;
+        }
+        MenuOutcome::None
+    }
 }
 
 /* ---------- helpers UI ---------- */
 
+fn on_off(v: bool) -> &'static str {
+    if v { "Sí" } else { "No" }
+}
+
+fn filter_label(f: Filter) -> &'static str {
+    match f {
+        Filter::Nearest => "Ninguno",
+        Filter::Bilinear => "Bilineal",
+        Filter::Mipmapped => "Mipmaps",
+    }
+}
+
+fn upscale_label(u: Upscale) -> &'static str {
+    match u {
+        Upscale::Integer => "Entero",
+        Upscale::Aspect => "Proporcional",
+    }
+}
+
+/// Fila de opción: etiqueta a la izquierda y valor a la derecha; clic = cambiar.
+fn option_row(
+    d: &mut RaylibDrawHandle,
+    mouse_pos: Vector2,
+    click_left: bool,
+    r: Rectangle,
+    label: &str,
+    value: &str,
+) -> bool {
+    let hover = point_in_rect(mouse_pos, r);
+    draw_button_box(d, r, hover);
+    d.draw_text(
+        label,
+        (r.x + 16.0) as i32,
+        (r.y + (r.height - 22.0) / 2.0) as i32,
+        22,
+        Color::RAYWHITE,
+    );
+    let tw = d.measure_text(value, 22);
+    d.draw_text(
+        value,
+        (r.x + r.width - 16.0) as i32 - tw,
+        (r.y + (r.height - 22.0) / 2.0) as i32,
+        22,
+        Color::GOLD,
+    );
+    hover && click_left
+}
+
 fn rect(x: f32, y: f32, w: f32, h: f32) -> Rectangle {
     Rectangle {
         x,
@@ -1,12 +1,18 @@
+use crate::camera::ViewOffset;
+use crate::flashlight::Flashlight;
 use crate::maze::Maze;
 use raylib::prelude::*;
 
 pub struct Player {
     pub pos: Vector2,    // píxeles
     pub a: f32,          // ángulo (rad)
+    pub pitch: f32,      // mirar arriba (+) / abajo (-), rad; se dibuja con y-shearing
     pub fov: f32,        // field of view
     pub move_speed: f32, // px/seg
     pub rot_speed: f32,  // rad/seg
+    pub flashlight: Flashlight,
+    pub view: ViewOffset, // balanceo / sacudida de cámara de este frame
+    pub sprinting: bool,  // corrió este frame (Shift o gatillos)
 }
 
 impl Player {
@@ -32,9 +38,27 @@ impl Player {
         Self {
             pos: Vector2::new(x, y),
             a,
+            pitch: 0.0,
             fov,
             move_speed: 120.0,
             rot_speed: 2.5,
+            flashlight: Flashlight::new(),
+            view: ViewOffset::default(),
+            sprinting: false,
         }
     }
+
+    /// Cuánto baja (px) el horizonte respecto al centro de una pantalla de
+    /// ancho `w` por el `pitch` (y-shearing: muros, piso y sprites lo comparten).
+    #[inline]
+    pub fn horizon_shift(&self, w: i32) -> f32 {
+        let dist_to_proj = (w as f32 * 0.5) / (self.fov * 0.5).tan();
+        (self.pitch + self.view.pitch).tan() * dist_to_proj
+    }
+
+    /// Altura de los ojos sobre el piso, en bloques (0.5 = a media pared).
+    #[inline]
+    pub fn eye_height(&self) -> f32 {
+        (0.5 + self.view.eye).clamp(0.05, 0.95)
+    }
 }
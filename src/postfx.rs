@@ -0,0 +1,237 @@
+use crate::framebuffer::FrameBuffer;
+use raylib::prelude::*;
+use rayon::prelude::*;
+
+const VIGNETTE_STRENGTH: f32 = 0.55; // cuánto oscurece en las esquinas
+const GRAIN_AMOUNT: f32 = 14.0; // ± niveles de brillo del ruido
+const SCANLINE_DARK: f32 = 0.78; // brillo de las líneas impares
+const CHROMA_PX: f32 = 3.0; // corrimiento de rojo/azul en las esquinas (a 800 px de ancho)
+const DITHER_LEVELS: f32 = 6.0; // niveles por canal de la paleta (6³ = 216 colores)
+const PANIC_RISE: f32 = 3.0; // suavizado de la amenaza al subir / bajar
+const PANIC_FALL: f32 = 1.2;
+
+/// Matriz de Bayer 4x4 (umbrales del tramado ordenado).
+const BAYER4: [[f32; 4]; 4] = [
+    [0.0, 8.0, 2.0, 10.0],
+    [12.0, 4.0, 14.0, 6.0],
+    [3.0, 11.0, 1.0, 9.0],
+    [15.0, 7.0, 13.0, 5.0],
+];
+
+/// Un efecto de la cadena de post-proceso.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Effect {
+    Chromatic, // aberración cromática: rojo y azul se separan hacia los bordes
+    Panic,     // pulso rojo que late más fuerte con el monstruo cerca
+    Vignette,  // esquinas oscuras
+    Scanlines, // líneas de monitor viejo
+    Grain,     // ruido de película
+    Dither,    // tramado ordenado a una paleta corta
+}
+
+impl Effect {
+    /// Todos los efectos, en el orden en que se aplican.
+    pub const ALL: [Effect; 6] = [
+        Effect::Chromatic,
+        Effect::Panic,
+        Effect::Vignette,
+        Effect::Scanlines,
+        Effect::Grain,
+        Effect::Dither,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Effect::Chromatic => "Aberración cromática",
+            Effect::Panic => "Pulso de pánico",
+            Effect::Vignette => "Viñeta",
+            Effect::Scanlines => "Líneas de barrido",
+            Effect::Grain => "Grano",
+            Effect::Dither => "Paleta con tramado",
+        }
+    }
+}
+
+/// Estado animado del post-proceso (grano y pulso); los efectos activos
+/// vienen de las opciones.
+pub struct PostFx {
+    time: f32,
+    panic: f32, // 0..1, amenaza suavizada
+}
+
+impl PostFx {
+    pub fn new() -> Self {
+        Self {
+            time: 0.0,
+            panic: 0.0,
+        }
+    }
+
+    /// `threat` es 0..1 según la distancia al monstruo (ver `AudioAssets::threat`).
+    pub fn update(&mut self, dt: f32, threat: f32) {
+        self.time += dt;
+        let rate = if threat > self.panic {
+            PANIC_RISE
+        } else {
+            PANIC_FALL
+        };
+        let k = 1.0 - (-dt * rate).exp();
+        self.panic += (threat.clamp(0.0, 1.0) - self.panic) * k;
+    }
+
+    /// Aplica al framebuffer los efectos de `enabled`, en el orden de `Effect::ALL`.
+    pub fn apply(&self, framebuffer: &mut FrameBuffer, enabled: &[Effect]) {
+        for effect in Effect::ALL.into_iter().filter(|e| enabled.contains(e)) {
+            match effect {
+                Effect::Chromatic => chromatic(framebuffer),
+                Effect::Panic => self.panic_pulse(framebuffer),
+                Effect::Vignette => vignette(framebuffer),
+                Effect::Scanlines => scanlines(framebuffer),
+                Effect::Grain => grain(framebuffer, (self.time * 60.0) as u32),
+                Effect::Dither => dither(framebuffer),
+            }
+        }
+    }
+
+    fn panic_pulse(&self, framebuffer: &mut FrameBuffer) {
+        if self.panic < 0.01 {
+            return;
+        }
+        // latido: más rápido y más fuerte cuanto más cerca
+        let bpm = 60.0 + 80.0 * self.panic;
+        let beat = (self.time * bpm / 60.0 * std::f32::consts::TAU).sin() * 0.5 + 0.5;
+        let strength = self.panic * (0.35 + 0.65 * beat * beat);
+        for_each_pixel(framebuffer, |c, r2| {
+            // sobre todo en los bordes, para no tapar lo que tienes enfrente
+            let k = strength * (0.25 + 0.75 * r2.min(1.0)) * 0.6;
+            c.r = (c.r as f32 + (200.0 - c.r as f32) * k) as u8;
+            c.g = (c.g as f32 * (1.0 - k)) as u8;
+            c.b = (c.b as f32 * (1.0 - k)) as u8;
+        });
+    }
+}
+
+/// Recorre el framebuffer en paralelo. `r2` es la distancia al centro al
+/// cuadrado, normalizada para que valga 1 en las esquinas.
+fn for_each_pixel(framebuffer: &mut FrameBuffer, f: impl Fn(&mut Color, f32) + Sync) {
+    let w = framebuffer.width.max(1) as usize;
+    let (hw, hh) = (w as f32 * 0.5, framebuffer.height as f32 * 0.5);
+    let inv = 1.0 / (hw * hw + hh * hh);
+    framebuffer
+        .pixels
+        .par_chunks_mut(w)
+        .enumerate()
+        .for_each(|(y, row)| {
+            let dy = y as f32 + 0.5 - hh;
+            for (x, c) in row.iter_mut().enumerate() {
+                let dx = x as f32 + 0.5 - hw;
+                f(c, (dx * dx + dy * dy) * inv);
+            }
+        });
+}
+
+fn vignette(framebuffer: &mut FrameBuffer) {
+    for_each_pixel(framebuffer, |c, r2| {
+        let t = ((r2 - 0.25) / 0.75).clamp(0.0, 1.0);
+        let k = 1.0 - VIGNETTE_STRENGTH * t * t * (3.0 - 2.0 * t);
+        c.r = (c.r as f32 * k) as u8;
+        c.g = (c.g as f32 * k) as u8;
+        c.b = (c.b as f32 * k) as u8;
+    });
+}
+
+fn scanlines(framebuffer: &mut FrameBuffer) {
+    let w = framebuffer.width.max(1) as usize;
+    framebuffer
+        .pixels
+        .par_chunks_mut(w)
+        .skip(1)
+        .step_by(2)
+        .for_each(|row| {
+            for c in row {
+                c.r = (c.r as f32 * SCANLINE_DARK) as u8;
+                c.g = (c.g as f32 * SCANLINE_DARK) as u8;
+                c.b = (c.b as f32 * SCANLINE_DARK) as u8;
+            }
+        });
+}
+
+fn grain(framebuffer: &mut FrameBuffer, frame: u32) {
+    let w = framebuffer.width.max(1) as usize;
+    framebuffer
+        .pixels
+        .par_chunks_mut(w)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for (x, c) in row.iter_mut().enumerate() {
+                let n = hash(x as u32, y as u32, frame) as f32 / u32::MAX as f32;
+                let d = (n - 0.5) * 2.0 * GRAIN_AMOUNT;
+                c.r = (c.r as f32 + d).clamp(0.0, 255.0) as u8;
+                c.g = (c.g as f32 + d).clamp(0.0, 255.0) as u8;
+                c.b = (c.b as f32 + d).clamp(0.0, 255.0) as u8;
+            }
+        });
+}
+
+/// Rojo hacia afuera y azul hacia adentro, más separados lejos del centro.
+fn chromatic(framebuffer: &mut FrameBuffer) {
+    let src = framebuffer.pixels.clone();
+    let (w, h) = (framebuffer.width.max(1), framebuffer.height.max(1));
+    let (hw, hh) = (w as f32 * 0.5, h as f32 * 0.5);
+    // el mismo corrimiento relativo a cualquier resolución interna
+    let shift = CHROMA_PX * w as f32 / 800.0;
+    let at = |x: f32, y: f32| {
+        let xi = (x as i32).clamp(0, w - 1);
+        let yi = (y as i32).clamp(0, h - 1);
+        src[(yi * w + xi) as usize]
+    };
+    framebuffer
+        .pixels
+        .par_chunks_mut(w as usize)
+        .enumerate()
+        .for_each(|(y, row)| {
+            let ny = (y as f32 + 0.5 - hh) / hh;
+            for (x, c) in row.iter_mut().enumerate() {
+                let nx = (x as f32 + 0.5 - hw) / hw;
+                // corrimiento proporcional a la distancia al centro
+                let (ox, oy) = (nx * shift, ny * shift);
+                c.r = at(x as f32 + ox, y as f32 + oy).r;
+                c.b = at(x as f32 - ox, y as f32 - oy).b;
+            }
+        });
+}
+
+/// Tramado ordenado (Bayer 4x4) a `DITHER_LEVELS` niveles por canal.
+fn dither(framebuffer: &mut FrameBuffer) {
+    let w = framebuffer.width.max(1) as usize;
+    let step = 255.0 / (DITHER_LEVELS - 1.0);
+    framebuffer
+        .pixels
+        .par_chunks_mut(w)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for (x, c) in row.iter_mut().enumerate() {
+                let t = (BAYER4[y % 4][x % 4] + 0.5) / 16.0 - 0.5;
+                let q = |v: u8| {
+                    let level = (v as f32 / step + t)
+                        .round()
+                        .clamp(0.0, DITHER_LEVELS - 1.0);
+                    (level * step) as u8
+                };
+                c.r = q(c.r);
+                c.g = q(c.g);
+                c.b = q(c.b);
+            }
+        });
+}
+
+/// Hash entero barato para el grano (distinto en cada frame).
+#[inline]
+fn hash(x: u32, y: u32, frame: u32) -> u32 {
+    let mut h = x
+        .wrapping_mul(374_761_393)
+        .wrapping_add(y.wrapping_mul(668_265_263))
+        .wrapping_add(frame.wrapping_mul(2_246_822_519));
+    h = (h ^ (h >> 13)).wrapping_mul(1_274_126_177);
+    h ^ (h >> 16)
+}
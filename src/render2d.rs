@@ -1,36 +1,204 @@
-use crate::draw_utils::draw_rect;
-use crate::framebuffer::FrameBuffer;
+use crate::draw_utils::draw_disc;
+use crate::framebuffer::{FrameBuffer, Upscale};
 use crate::maze::Maze;
+use crate::player::Player;
 use raylib::prelude::*;
 
-fn color_for_cell(c: char) -> Color {
-    match c {
-        '#' => Color::DARKGRAY,
-        'A' => Color::RED,
-        'B' => Color::GREEN,
-        'C' => Color::BLUE,
-        'P' => Color::GOLD,
-        'E' => Color::LIME,
-        '.' | ' ' => Color::new(30, 30, 35, 255),
-        _ => Color::LIGHTGRAY,
+const AUTOMAP_CELL: i32 = 8; // px por celda en el mapa completo (luego se escala)
+const MINIMAP_SIZE: i32 = 168; // lado del minimapa, en px de ventana
+const MINIMAP_CELLS: f32 = 13.0; // celdas que entran a lo ancho del minimapa
+const MINIMAP_MARGIN: i32 = 12;
+const SLAB_HALF: f32 = 0.14; // medio grosor de puertas, rejas y ventanas (en celdas)
+
+const UNEXPLORED: Color = Color::new(8, 8, 10, 255);
+const MINIMAP_FOG: Color = Color::new(0, 0, 0, 150);
+const MINIMAP_RIM: Color = Color::new(200, 200, 200, 255);
+const PLAYER_COLOR: Color = Color::new(255, 220, 90, 255);
+const ENEMY_COLOR: Color = Color::new(230, 30, 30, 255);
+
+/// Color del mapa en el punto (wx, wy) del mundo (px); `None` si la celda
+/// todavía no se exploró.
+fn map_color(maze: &Maze, wx: f32, wy: f32) -> Option<Color> {
+    let bs = maze.block_size as f32;
+    let (i, j) = ((wx / bs).floor() as i32, (wy / bs).floor() as i32);
+    if !maze.is_explored(i, j) {
+        return None;
     }
+    let c = maze.tile_at(i, j);
+    let Some(wall) = maze.thin_wall_at(i, j) else {
+        return Some(Maze::cell_color(c));
+    };
+    // puertas, rejas y ventanas: la losa sobre el piso, corrida según lo
+    // abierta que esté (igual que en `ThinRay::hit`)
+    let (fx, fy) = (wx / bs - i as f32, wy / bs - j as f32);
+    let (s, t) = if wall.along_y { (fy, fx) } else { (fx, fy) };
+    let on_slab = (t - 0.5).abs() < SLAB_HALF && s < 1.0 - wall.open;
+    Some(Maze::cell_color(if on_slab { c } else { '.' }))
 }
 
-pub fn render_maze_2d(framebuffer: &mut FrameBuffer, maze: &Maze) {
-    // fondo
+/// Mapa completo, una celda = `AUTOMAP_CELL` px, con el norte arriba.
+pub fn render_maze_2d(
+    framebuffer: &mut FrameBuffer,
+    maze: &Maze,
+    player: &Player,
+    enemy: Option<Vector2>,
+) {
+    let k = maze.block_size as f32 / AUTOMAP_CELL as f32; // px de mundo por px de mapa
     for y in 0..framebuffer.height {
         for x in 0..framebuffer.width {
-            framebuffer.set_color(Color::BLACK);
-            framebuffer.set_pixel(x, y);
+            let (wx, wy) = ((x as f32 + 0.5) * k, (y as f32 + 0.5) * k);
+            let c = map_color(maze, wx, wy).unwrap_or(UNEXPLORED);
+            framebuffer.put_pixel(x, y, c);
+        }
+    }
+
+    let to_map = |p: Vector2| ((p.x / k) as i32, (p.y / k) as i32);
+    if let Some(e) = enemy {
+        let (ex, ey) = to_map(e);
+        draw_disc(framebuffer, ex, ey, AUTOMAP_CELL / 3, ENEMY_COLOR);
+    }
+    let (px, py) = to_map(player.pos);
+    let dir = Vector2::new(player.a.cos(), player.a.sin());
+    draw_marker(framebuffer, px, py, dir, AUTOMAP_CELL / 3);
+}
+
+/// Minimapa circular centrado en el jugador, girado para que "adelante"
+/// quede siempre arriba. Fuera del círculo queda transparente.
+pub fn render_minimap(
+    framebuffer: &mut FrameBuffer,
+    maze: &Maze,
+    player: &Player,
+    enemy: Option<Vector2>,
+) {
+    let size = framebuffer.width as f32;
+    let r = size * 0.5;
+    let k = maze.block_size as f32 * MINIMAP_CELLS / size; // px de mundo por px de mapa
+    // adelante y derecha del jugador (y crece hacia abajo)
+    let (fx, fy) = (player.a.cos(), player.a.sin());
+    let (rx, ry) = (-fy, fx);
+
+    for y in 0..framebuffer.height {
+        for x in 0..framebuffer.width {
+            let (dx, dy) = (x as f32 + 0.5 - r, y as f32 + 0.5 - r);
+            let d2 = dx * dx + dy * dy;
+            let c = if d2 > r * r {
+                Color::BLANK
+            } else if d2 > (r - 2.0) * (r - 2.0) {
+                MINIMAP_RIM
+            } else {
+                // arriba en pantalla = adelante; derecha = derecha
+                let wx = player.pos.x + (fx * -dy + rx * dx) * k;
+                let wy = player.pos.y + (fy * -dy + ry * dx) * k;
+                map_color(maze, wx, wy)
+                    .map(|c| Color::new(c.r, c.g, c.b, 220))
+                    .unwrap_or(MINIMAP_FOG)
+            };
+            framebuffer.put_pixel(x, y, c);
+        }
+    }
+
+    if let Some(e) = enemy {
+        let d = e - player.pos;
+        let ex = r + (d.x * rx + d.y * ry) / k;
+        let ey = r - (d.x * fx + d.y * fy) / k;
+        let (dx, dy) = (ex - r, ey - r);
+        if dx * dx + dy * dy < (r - 6.0) * (r - 6.0) {
+            draw_disc(framebuffer, ex as i32, ey as i32, 4, ENEMY_COLOR);
+        }
+    }
+    draw_marker(framebuffer, r as i32, r as i32, Vector2::new(0.0, -1.0), 4);
+}
+
+/// Jugador: un punto con una rayita hacia donde mira.
+fn draw_marker(framebuffer: &mut FrameBuffer, x: i32, y: i32, dir: Vector2, r: i32) {
+    draw_disc(framebuffer, x, y, r.max(1), PLAYER_COLOR);
+    for s in 0..=(r * 3) {
+        let px = x + (dir.x * s as f32).round() as i32;
+        let py = y + (dir.y * s as f32).round() as i32;
+        framebuffer.put_pixel(px, py, PLAYER_COLOR);
+    }
+}
+
+/// Minimapa en una esquina y mapa completo (Tab): cada uno se pinta en CPU en
+/// su propio framebuffer y se dibuja sobre la escena a resolución de ventana.
+pub struct MapOverlay {
+    pub show_automap: bool,
+    explored: f32, // fracción del piso explorada, para el rótulo del mapa completo
+    minimap: FrameBuffer,
+    minimap_tex: Texture2D,
+    automap: FrameBuffer,
+    automap_tex: Texture2D,
+}
+
+impl MapOverlay {
+    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread) -> Result<Self, String> {
+        let minimap = FrameBuffer::new(MINIMAP_SIZE, MINIMAP_SIZE, Color::BLANK);
+        let automap = FrameBuffer::new(AUTOMAP_CELL, AUTOMAP_CELL, UNEXPLORED);
+        Ok(Self {
+            show_automap: false,
+            explored: 0.0,
+            minimap_tex: minimap.create_texture(rl, thread)?,
+            automap_tex: automap.create_texture(rl, thread)?,
+            minimap,
+            automap,
+        })
+    }
+
+    /// Pinta y sube el mapa que se esté mostrando. `enemy` solo se pasa si
+    /// el jugador lo tiene a la vista.
+    pub fn render(
+        &mut self,
+        rl: &mut RaylibHandle,
+        thread: &RaylibThread,
+        maze: &Maze,
+        player: &Player,
+        enemy: Option<Vector2>,
+    ) -> Result<(), String> {
+        if !self.show_automap {
+            render_minimap(&mut self.minimap, maze, player, enemy);
+            return self.minimap.upload(&mut self.minimap_tex);
+        }
+        // cada nivel tiene su tamaño: se recrea al cambiar
+        let w = maze.width as i32 * AUTOMAP_CELL;
+        let h = maze.height as i32 * AUTOMAP_CELL;
+        if (self.automap.width, self.automap.height) != (w, h) {
+            self.automap = FrameBuffer::new(w, h, UNEXPLORED);
+            self.automap_tex = self.automap.create_texture(rl, thread)?;
         }
+        render_maze_2d(&mut self.automap, maze, player, enemy);
+        self.explored = maze.explored_fraction();
+        self.automap.upload(&mut self.automap_tex)
     }
 
-    let bs = maze.block_size;
-    for (j, row) in maze.grid.iter().enumerate() {
-        for (i, &cell) in row.iter().enumerate() {
-            let xo = (i as i32) * bs;
-            let yo = (j as i32) * bs;
-            draw_rect(framebuffer, xo, yo, bs, bs, color_for_cell(cell));
+    pub fn draw(&self, d: &mut RaylibDrawHandle) {
+        let (sw, sh) = (d.get_screen_width(), d.get_screen_height());
+        if !self.show_automap {
+            let x = sw - MINIMAP_SIZE - MINIMAP_MARGIN;
+            d.draw_texture(&self.minimap_tex, x, MINIMAP_MARGIN, Color::WHITE);
+            return;
         }
+        d.draw_rectangle(0, 0, sw, sh, Color::new(0, 0, 0, 200));
+        let m = 2 * MINIMAP_MARGIN;
+        let mut dst = self
+            .automap
+            .fit_rect(sw - 2 * m, sh - 2 * m, Upscale::Aspect);
+        dst.x += m as f32;
+        dst.y += m as f32;
+        let src = Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: self.automap.width as f32,
+            height: self.automap.height as f32,
+        };
+        d.draw_texture_pro(
+            &self.automap_tex,
+            src,
+            dst,
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+        let label = format!("Explorado: {:.0}%", self.explored * 100.0);
+        d.draw_text(&label, m, sh - m + 2, 18, Color::LIGHTGRAY);
     }
 }
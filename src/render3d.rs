@@ -1,12 +1,22 @@
-use crate::caster::cast_ray_topdown;
+use crate::caster::{Face, Hit, RayTrace, cast_ray};
+use crate::flashlight::{Lighting, apply_light};
 use crate::framebuffer::FrameBuffer;
 use crate::maze::Maze;
 use crate::player::Player;
-use crate::textures::Textures;
+use crate::textures::{CpuTexture, Filter, Textures};
 use raylib::prelude::*;
+use rayon::prelude::*;
+use std::f32::consts::TAU;
 
+/// Cuánta niebla (0..1) hay a `dist` px; la misma curva para muros, piso,
+/// techo y sprites.
 #[inline]
-fn fog_mix(mut c: Color, fog: Color, t: f32) -> Color {
+pub fn fog_amount(dist: f32) -> f32 {
+    1.0 - (-dist * 0.010).exp()
+}
+
+#[inline]
+pub fn fog_mix(mut c: Color, fog: Color, t: f32) -> Color {
     let t = t.clamp(0.0, 1.0);
     c.r = (c.r as f32 * (1.0 - t) + fog.r as f32 * t) as u8;
     c.g = (c.g as f32 * (1.0 - t) + fog.g as f32 * t) as u8;
@@ -14,11 +24,85 @@ fn fog_mix(mut c: Color, fog: Color, t: f32) -> Color {
     c
 }
 
+/// Filas por banda en la pasada paralela (cada banda es un slice disjunto).
+const BAND_ROWS: usize = 8;
+
+const SKY_REPEAT: f32 = 2.0; // veces que el panorama da la vuelta completa
+const SKY_SPAN: f32 = 0.7; // rad por encima del horizonte que cubre el panorama
+
+/// Tramo de muro de una columna, calculado una sola vez por impacto.
+struct WallSpan<'t> {
+    dist: f32,
+    top: i32, // filas visibles de la cara (ya recortadas a la pantalla)
+    bot: i32,
+    wall_top: f32, // borde superior sin recortar y alto de un bloque en pantalla, para la v
+    wall_h: f32,
+    v0: f32, // v del borde superior: un muro bajo muestra solo la parte de abajo
+    tex: &'t CpuTexture,
+    decal: Option<(&'t CpuTexture, f32)>, // calcomanía de la cara y su nivel de mipmap
+    u: f32,
+    filter: Filter,
+    lod: f32,   // nivel de mipmap: log2(texels por píxel de pantalla)
+    hit_x: f32, // punto de impacto en celdas (para el lightmap)
+    hit_y: f32,
+    side_shade: f32,
+    fade: f32,
+    fog_t: f32,
+    cap: Option<Cap>, // tapa de un muro bajo, si se la ve desde arriba
+}
+
+/// Tapa de un muro bajo: va desde el borde lejano (donde el rayo sale de la
+/// celda) hasta el borde superior de la cara.
+struct Cap {
+    top: i32,
+    plane_h: f32, // (ojos - alto) * dist_to_proj * bs: fila -> distancia, como el piso
+    exit: f32,    // distancia perpendicular (px) y punto (celdas) del borde lejano
+    exit_x: f32,
+    exit_y: f32,
+}
+
+/// Columna de pantalla: el muro opaco y, delante, los muros bajos y las
+/// rejas / ventanas que el rayo atravesó (del más lejano al más cercano,
+/// para componer).
+struct WallColumn<'t> {
+    wall: WallSpan<'t>,
+    layers: Vec<WallSpan<'t>>,
+}
+
+impl WallSpan<'_> {
+    /// Texel de la fila `sy` con sombreado y niebla; la alpha es la de la textura.
+    #[inline]
+    fn shade(&self, sy: i32, fog: Color) -> Color {
+        let v_eps = 0.5 / self.tex.h as f32;
+        // v sobre el muro completo, aunque esté recortado por arriba/abajo
+        let v = self.v0 + (sy as f32 + 0.5 - self.wall_top) / self.wall_h.max(1.0);
+        let v = v.clamp(v_eps, 1.0 - v_eps);
+        let mut c = self.tex.sample_filtered(self.u, v, self.filter, self.lod);
+        if let Some((decal, lod)) = self.decal {
+            // encima del muro según su alpha; la alpha del muro (rejas) no cambia
+            let d = decal.sample_filtered(self.u, v, self.filter, lod);
+            let k = d.a as f32 / 255.0;
+            c.r = (d.r as f32 * k + c.r as f32 * (1.0 - k)) as u8;
+            c.g = (d.g as f32 * k + c.g as f32 * (1.0 - k)) as u8;
+            c.b = (d.b as f32 * k + c.b as f32 * (1.0 - k)) as u8;
+        }
+        c.r = (c.r as f32 * self.side_shade * self.fade) as u8;
+        c.g = (c.g as f32 * self.side_shade * self.fade) as u8;
+        c.b = (c.b as f32 * self.side_shade * self.fade) as u8;
+        fog_mix(c, fog, self.fog_t)
+    }
+}
+
+/// Dibuja la escena y devuelve el z-buffer por píxel (distancia
+/// perpendicular en px; infinito donde solo hay piso, techo o cielo).
+/// `filter` es la calidad de muestreo de muros, piso y techo. Las celdas que
+/// recorren los rayos quedan marcadas como vistas en `maze`.
 pub fn render3d(
     framebuffer: &mut FrameBuffer,
-    maze: &Maze,
+    maze: &mut Maze,
     player: &Player,
     textures: &Textures,
+    filter: Filter,
 ) -> Vec<f32> {
     let w = framebuffer.width as i32;
     let h = framebuffer.height as i32;
@@ -26,33 +110,101 @@ pub fn render3d(
     let hh = h as f32 * 0.5;
     let dist_to_proj = hw / (player.fov * 0.5).tan();
     let bs = maze.block_size as f32;
+    // y-shearing: el pitch solo desplaza el horizonte; los ojos pueden
+    // subir o bajar respecto a media pared (balanceo de cámara)
+    let horizon = hh + player.horizon_shift(w);
+    let eye = player.eye_height();
 
-    // Colores base
-    let sky = Color {
-        r: 20,
-        g: 24,
-        b: 40,
-        a: 255,
-    };
-    let floor = Color {
-        r: 30,
-        g: 22,
-        b: 18,
-        a: 255,
-    };
+    // Todo se funde a lo lejos con la niebla del nivel; donde no hay techo se
+    // ve el panorama (o, sin panorama, la misma niebla)
+    let fog = maze.fog;
+    let sky = Sky::new(maze, textures, player, w, horizon, dist_to_proj, filter);
+
+    // ====== MUROS (fase 1: un rayo por columna, en paralelo) ======
+    let span = |hit: &Hit| {
+        let dist = hit.perp_distance;
+
+        // el piso queda `eye` bloques por debajo de los ojos y el borde
+        // superior, `height - eye` por encima
+        let stake_h = (bs * dist_to_proj) / dist;
+        let wall_top = horizon - stake_h * (hit.height - eye);
+        let top = (wall_top.max(0.0)) as i32;
+        let bot = ((horizon + stake_h * eye).min(h as f32 - 1.0)) as i32;
 
-    // Cielo “sólido” 
-    framebuffer.set_color(sky);
-    for y in 0..(hh as i32) {
-        for x in 0..w {
-            framebuffer.set_pixel(x, y);
+        let tex = textures.get(if hit.jamb { 'j' } else { hit.impact });
+
+        // La cara golpeada viene exacta del DDA
+        let vertical = hit.face.is_vertical();
+        let mut u = if vertical {
+            (hit.hit_y / bs).fract()
+        } else {
+            (hit.hit_x / bs).fract()
+        };
+        u += hit.slide; // la textura de la puerta se corre con la losa
+        // u crece hacia la derecha de quien mira la cara
+        if matches!(hit.face, Face::East | Face::North) {
+            u = 1.0 - u;
         }
-    }
+        let u_eps = 0.5 / tex.w as f32;
+        u = u.clamp(u_eps, 1.0 - u_eps);
 
-    // ====== FLOOR CASTING (piso texturizado para todas las celdas) ======
-    let px = player.pos.x / bs;
-    let py = player.pos.y / bs;
+        // muro bajo visto desde arriba: la tapa llega hasta donde sale el rayo
+        let cap = (hit.height < eye && hit.exit > dist).then(|| {
+            let k = hit.exit / dist; // el punto lejano sigue el mismo rayo
+            Cap {
+                top: (horizon + (bs * dist_to_proj / hit.exit) * (eye - hit.height)).max(0.0)
+                    as i32,
+                plane_h: bs * dist_to_proj * (eye - hit.height),
+                exit: hit.exit,
+                exit_x: (player.pos.x + (hit.hit_x - player.pos.x) * k) / bs,
+                exit_y: (player.pos.y + (hit.hit_y - player.pos.y) * k) / bs,
+            }
+        });
 
+        // la calcomanía va sobre la cara entera, con la misma (u, v) que el muro
+        let decal = maze.decal_at(hit.cell, hit.face).map(|k| {
+            let d = textures.get(k);
+            (d, (d.h as f32 / stake_h).log2())
+        });
+
+        WallSpan {
+            dist,
+            top,
+            bot,
+            wall_top,
+            wall_h: stake_h,
+            v0: 1.0 - hit.height,
+            tex,
+            decal,
+            u,
+            filter,
+            // un bloque de alto ocupa `stake_h` px y `tex.h` texels
+            lod: (tex.h as f32 / stake_h).log2(),
+            hit_x: hit.hit_x / bs,
+            hit_y: hit.hit_y / bs,
+            side_shade: if vertical { 0.82 } else { 1.0 },
+            fade: (1.0 / (1.0 + dist * 0.002)).clamp(0.3, 1.0),
+            fog_t: fog_amount(dist),
+            cap,
+        }
+    };
+    let (columns, traced): (Vec<WallColumn>, Vec<Vec<(i32, i32)>>) = (0..w as usize)
+        .into_par_iter()
+        .map(|sx| {
+            let lerp = sx as f32 / (w as f32 - 1.0).max(1.0);
+            let ray_angle = player.a - player.fov * 0.5 + player.fov * lerp;
+
+            let mut trace = RayTrace::default();
+            let hit = cast_ray(maze, player, ray_angle, &mut trace);
+            let column = WallColumn {
+                wall: span(&hit),
+                layers: trace.layers.iter().rev().map(&span).collect(),
+            };
+            (column, trace.cells)
+        })
+        .unzip();
+
+    // ====== FLOOR / CEILING CASTING (texturizado para todas las celdas) ======
     // Dirección de la cámara y plano (perpendicular) de longitud tan(fov/2)
     let dirx = player.a.cos();
     let diry = player.a.sin();
@@ -61,110 +213,307 @@ pub fn render3d(
     let planey = dirx * plane_len;
 
     // Rayos del borde izquierdo y derecho de la pantalla
-    let r0x = dirx - planex;
-    let r0y = diry - planey;
-    let r1x = dirx + planex;
-    let r1y = diry + planey;
-
-    // Textura del piso 
-    let floor_tex = textures.get('.');
-
-    // Recorremos filas desde el horizonte hacia abajo
-    for sy in (hh as i32)..h {
-        let p = sy as f32 - hh + 0.5; 
-        if p <= 0.0 {
-            continue;
-        }
-        let pos_z = hh;
-        let row_dist = pos_z / p; 
+    let view = PlaneView {
+        px: player.pos.x / bs,
+        py: player.pos.y / bs,
+        r0x: dirx - planex,
+        r0y: diry - planey,
+        r1x: dirx + planex,
+        r1y: diry + planey,
+        bs,
+        filter,
+        fog,
+    };
 
-        let step_x = row_dist * (r1x - r0x) / w as f32;
-        let step_y = row_dist * (r1y - r0y) / w as f32;
+    // Texturas de piso y techo por celda (el techo es opcional por nivel)
+    let floor_at = |i: i32, j: i32| Some(maze.floor_at(i, j));
+    let ceiling_at = |i: i32, j: i32| maze.ceiling_at(i, j);
 
-        let mut world_x = px + row_dist * r0x;
-        let mut world_y = py + row_dist * r0y;
+    // Luz ambiente del nivel + lámparas + linterna
+    let lighting = Lighting::new(player, maze, w, h);
 
-        let dist_px = row_dist * bs;
-        let fog_t = 1.0 - (-dist_px * 0.010).exp();
+    let floor_h = eye * dist_to_proj;
+    let ceil_h = (1.0 - eye) * dist_to_proj;
 
-        for sx in 0..w {
-            let u = world_x.fract();
-            let v = world_y.fract();
+    // ====== Fase 2: bandas de filas en paralelo (techo/piso + muros) ======
+    // z-buffer por píxel: muros, muros bajos y barrotes; el piso no tapa
+    let row_len = w as usize;
+    let mut depth = vec![f32::INFINITY; row_len * h as usize];
+    framebuffer
+        .pixels
+        .par_chunks_mut(row_len * BAND_ROWS)
+        .zip(depth.par_chunks_mut(row_len * BAND_ROWS))
+        .enumerate()
+        .for_each(|(band, (rows, depth_rows))| {
+            let rows = rows.chunks_mut(row_len).zip(depth_rows.chunks_mut(row_len));
+            for (k, (row, depth_row)) in rows.enumerate() {
+                let sy = (band * BAND_ROWS + k) as i32;
 
-            let mut c = floor_tex.sample(u, v);
-            c = fog_mix(c, sky, fog_t);
+                // Piso desde el horizonte hacia abajo; arriba techo o cielo “sólido”.
+                // Con la misma proyección que los muros, una fila a `p` px del
+                // horizonte está a altura_de_ojos * dist_to_proj / p celdas.
+                let p_floor = sy as f32 - horizon + 0.5;
+                let p_ceil = horizon - sy as f32 - 0.5;
+                let plane_dist = if p_floor > 0.0 {
+                    let d = floor_h / p_floor;
+                    let step = d * d / floor_h;
+                    cast_plane_row(row, sy, d, step, &view, &sky, textures, floor_at);
+                    Some(d)
+                } else if maze.has_ceiling && p_ceil > 0.0 {
+                    let d = ceil_h / p_ceil;
+                    let step = d * d / ceil_h;
+                    cast_plane_row(row, sy, d, step, &view, &sky, textures, ceiling_at);
+                    Some(d)
+                } else {
+                    sky.fill_row(row, sy);
+                    None
+                };
 
-            framebuffer.set_color(c);
-            framebuffer.set_pixel(sx, sy);
+                draw_wall_row(row, depth_row, sy, &columns, fog);
 
-            world_x += step_x;
-            world_y += step_y;
+                if !lighting.is_full() {
+                    light_row(row, sy, plane_dist, &view, &columns, &lighting);
+                }
+
+                draw_layers_row(row, depth_row, sy, horizon, &columns, fog, &lighting);
+            }
+        });
+
+    // niebla de guerra: lo que barrieron los rayos ya aparece en el mapa
+    maze.mark_seen(traced.iter().flatten().copied());
+    depth
+}
+
+/// Rayos de los bordes de la cámara, en unidades de celda.
+struct PlaneView {
+    px: f32,
+    py: f32,
+    r0x: f32,
+    r0y: f32,
+    r1x: f32,
+    r1y: f32,
+    bs: f32,
+    filter: Filter,
+    fog: Color,
+}
+
+/// Fondo donde no hay techo: un panorama cilíndrico que gira con
+/// `player.a`, o un color liso si el nivel no tiene.
+struct Sky<'t> {
+    tex: Option<&'t CpuTexture>,
+    color: Color,
+    a0: f32, // ángulo de la columna 0 y cuánto avanza por columna (como los rayos)
+    da: f32,
+    horizon: f32,
+    dist_to_proj: f32,
+    filter: Filter,
+    lod: f32,
+}
+
+impl<'t> Sky<'t> {
+    fn new(
+        maze: &Maze,
+        textures: &'t Textures,
+        player: &Player,
+        w: i32,
+        horizon: f32,
+        dist_to_proj: f32,
+        filter: Filter,
+    ) -> Self {
+        let tex = maze.sky.map(|k| textures.get(k));
+        let da = player.fov / (w as f32 - 1.0).max(1.0);
+        Self {
+            tex,
+            color: maze.fog,
+            a0: player.a - player.fov * 0.5,
+            da,
+            horizon,
+            dist_to_proj,
+            filter,
+            lod: tex.map_or(0.0, |t| (t.w as f32 * da * SKY_REPEAT / TAU).log2()),
         }
     }
-    // ====================================================================
 
+    /// Color del cielo en el píxel (sx, sy).
+    #[inline]
+    fn at(&self, sx: usize, sy: i32) -> Color {
+        let Some(tex) = self.tex else {
+            return self.color;
+        };
+        let angle = self.a0 + self.da * sx as f32;
+        let u = (angle / TAU * SKY_REPEAT).rem_euclid(1.0);
+        // la altura sobre el horizonte como ángulo, para que mirar arriba no
+        // estire el panorama
+        let elev = ((self.horizon - sy as f32 - 0.5) / self.dist_to_proj).atan();
+        let v_eps = 0.5 / tex.h as f32;
+        let v = (1.0 - elev / SKY_SPAN).clamp(v_eps, 1.0 - v_eps);
+        tex.sample_filtered(u, v, self.filter, self.lod)
+    }
 
-    // ====== MUROS ======
-    let mut zbuffer = vec![f32::INFINITY; w as usize];
-
-    for sx in 0..w as usize {
-        let lerp = sx as f32 / (w as f32 - 1.0).max(1.0);
-        let ray_angle = player.a - player.fov * 0.5 + player.fov * lerp;
-        let ray_dx = ray_angle.cos();
-        let ray_dy = ray_angle.sin();
+    fn fill_row(&self, row: &mut [Color], sy: i32) {
+        if self.tex.is_none() {
+            row.fill(self.color);
+            return;
+        }
+        for (sx, px_out) in row.iter_mut().enumerate() {
+            *px_out = self.at(sx, sy);
+        }
+    }
+}
 
-        let hit = cast_ray_topdown(framebuffer, maze, player, ray_angle, false);
+/// Pinta una fila de piso o techo que está a `row_dist` celdas de la cámara.
+/// `row_step` es cuánto más lejos queda la fila siguiente (para el mipmap).
+/// `key_at` da la textura de cada celda; `None` deja ver el cielo.
+#[allow(clippy::too_many_arguments)]
+fn cast_plane_row(
+    row: &mut [Color],
+    sy: i32,
+    row_dist: f32,
+    row_step: f32,
+    view: &PlaneView,
+    sky: &Sky,
+    textures: &Textures,
+    key_at: impl Fn(i32, i32) -> Option<char>,
+) {
+    let w = row.len() as f32;
+    let step_x = row_dist * (view.r1x - view.r0x) / w;
+    let step_y = row_dist * (view.r1y - view.r0y) / w;
 
-        let delta = (ray_angle - player.a).cos().abs().max(1e-6);
-        let dist = hit.distance * delta;
-        zbuffer[sx] = dist;
+    let mut world_x = view.px + row_dist * view.r0x;
+    let mut world_y = view.py + row_dist * view.r0y;
 
-        let stake_h = (bs * dist_to_proj) / dist;
-        let top = ((hh - stake_h * 0.5).max(0.0)) as i32;
-        let bot = ((hh + stake_h * 0.5).min(h as f32 - 1.0)) as i32;
+    let dist_px = row_dist * view.bs;
+    let fog_t = fog_amount(dist_px);
 
-        let tex = textures.get(hit.impact);
+    // celdas que cubre un píxel: a lo ancho de la fila o hacia el fondo
+    let footprint = step_x.hypot(step_y).max(row_step);
 
-        let fx = hit.hit_x - ((hit.hit_x / bs).floor() * bs);
-        let fy = hit.hit_y - ((hit.hit_y / bs).floor() * bs);
-        let vertical = fx.min(bs - fx) < fy.min(bs - fy);
+    // Cache de la última celda: en filas largas casi siempre se repite la textura
+    let mut last_key: Option<char> = None;
+    let mut tex = textures.get('.');
+    let mut lod = 0.0;
 
-        let mut u = if vertical {
-            (hit.hit_y / bs).fract()
-        } else {
-            (hit.hit_x / bs).fract()
+    for (sx, px_out) in row.iter_mut().enumerate() {
+        let Some(key) = key_at(world_x.floor() as i32, world_y.floor() as i32) else {
+            *px_out = sky.at(sx, sy);
+            world_x += step_x;
+            world_y += step_y;
+            continue;
         };
-        if vertical && ray_dx > 0.0 {
-            u = 1.0 - u;
-        }
-        if !vertical && ray_dy < 0.0 {
-            u = 1.0 - u;
+        if last_key != Some(key) {
+            tex = textures.get(key);
+            last_key = Some(key);
+            lod = (tex.w as f32 * footprint).log2();
         }
-        let u_eps = 0.5 / tex.w as f32;
-        u = u.clamp(u_eps, 1.0 - u_eps);
 
-        let side_shade = if vertical { 0.82 } else { 1.0 };
-        let fog_t_wall = 1.0 - (-dist * 0.010).exp();
-        let fade = (1.0 / (1.0 + dist * 0.002)).clamp(0.3, 1.0);
+        let u = world_x.fract();
+        let v = world_y.fract();
 
-        let denom = (bot - top).max(1) as f32;
-        let v_eps = 0.5 / tex.h as f32;
-        let x = sx as i32;
+        let c = tex.sample_filtered(u, v, view.filter, lod);
+        *px_out = fog_mix(c, view.fog, fog_t);
 
-        for y in top..=bot {
-            let mut v = (y - top) as f32 / denom;
-            v = v.clamp(v_eps, 1.0 - v_eps);
+        world_x += step_x;
+        world_y += step_y;
+    }
+}
 
-            let mut c = tex.sample(u, v);
-            c.r = (c.r as f32 * side_shade * fade) as u8;
-            c.g = (c.g as f32 * side_shade * fade) as u8;
-            c.b = (c.b as f32 * side_shade * fade) as u8;
-            c = fog_mix(c, sky, fog_t_wall);
+/// Aplica la luz (ambiente + lámparas + linterna) a una fila ya compuesta.
+/// Profundidad y punto del mundo salen del muro si lo hay, o del piso/techo
+/// que está a `plane_dist` celdas.
+fn light_row(
+    row: &mut [Color],
+    sy: i32,
+    plane_dist: Option<f32>,
+    view: &PlaneView,
+    columns: &[WallColumn],
+    lighting: &Lighting,
+) {
+    let w = row.len() as f32;
+    for (sx, (px_out, col)) in row.iter_mut().zip(columns).enumerate() {
+        let (fx, fy) = (sx as f32, sy as f32);
+        let wall = &col.wall;
+        let light = if sy >= wall.top && sy <= wall.bot {
+            lighting.at(fx, fy, wall.dist, wall.hit_x, wall.hit_y)
+        } else if let Some(d) = plane_dist {
+            // mismo punto que muestreó `cast_plane_row`
+            let wx = view.px + d * (view.r0x + (view.r1x - view.r0x) * fx / w);
+            let wy = view.py + d * (view.r0y + (view.r1y - view.r0y) * fx / w);
+            lighting.at(fx, fy, d * view.bs, wx, wy)
+        } else {
+            lighting.sky()
+        };
+        *px_out = apply_light(*px_out, light);
+    }
+}
 
-            framebuffer.set_color(c);
-            framebuffer.set_pixel(x, y);
+/// Pinta en la fila `sy` los tramos de muro que la cruzan.
+fn draw_wall_row(
+    row: &mut [Color],
+    depth_row: &mut [f32],
+    sy: i32,
+    columns: &[WallColumn],
+    fog: Color,
+) {
+    for ((px_out, z), col) in row.iter_mut().zip(depth_row.iter_mut()).zip(columns) {
+        let wall = &col.wall;
+        if sy >= wall.top && sy <= wall.bot {
+            *px_out = wall.shade(sy, fog);
+            *z = wall.dist;
         }
     }
+}
 
-    zbuffer
+/// Compone en la fila `sy` los muros bajos y las rejas / ventanas de atrás
+/// hacia adelante, cada uno con su propia luz, mezclando según la alpha de
+/// su textura. Lo que queda casi opaco tapa a los sprites.
+fn draw_layers_row(
+    row: &mut [Color],
+    depth_row: &mut [f32],
+    sy: i32,
+    horizon: f32,
+    columns: &[WallColumn],
+    fog: Color,
+    lighting: &Lighting,
+) {
+    for (sx, ((px_out, z), col)) in row
+        .iter_mut()
+        .zip(depth_row.iter_mut())
+        .zip(columns)
+        .enumerate()
+    {
+        for layer in &col.layers {
+            let (mut c, dist, at) = if sy >= layer.top && sy <= layer.bot {
+                (layer.shade(sy, fog), layer.dist, (layer.hit_x, layer.hit_y))
+            } else if let Some(cap) = layer.cap.as_ref().filter(|c| sy >= c.top && sy < layer.top) {
+                // tapa: como una fila de piso, pero a la altura del muro
+                let d = cap.plane_h / (sy as f32 + 0.5 - horizon).max(0.5);
+                let d = d.clamp(layer.dist, cap.exit);
+                let f = (d - layer.dist) / (cap.exit - layer.dist);
+                let wx = layer.hit_x + (cap.exit_x - layer.hit_x) * f;
+                let wy = layer.hit_y + (cap.exit_y - layer.hit_y) * f;
+                let (u, v) = (wx.rem_euclid(1.0), wy.rem_euclid(1.0));
+                let mut c = layer.tex.sample_filtered(u, v, layer.filter, layer.lod);
+                let fade = (1.0 / (1.0 + d * 0.002)).clamp(0.3, 1.0) * 0.9;
+                c.r = (c.r as f32 * fade) as u8;
+                c.g = (c.g as f32 * fade) as u8;
+                c.b = (c.b as f32 * fade) as u8;
+                (fog_mix(c, fog, fog_amount(d)), d, (wx, wy))
+            } else {
+                continue;
+            };
+            if c.a < 8 {
+                continue;
+            }
+            if !lighting.is_full() {
+                c = apply_light(c, lighting.at(sx as f32, sy as f32, dist, at.0, at.1));
+            }
+            let k = c.a as f32 / 255.0;
+            px_out.r = (c.r as f32 * k + px_out.r as f32 * (1.0 - k)) as u8;
+            px_out.g = (c.g as f32 * k + px_out.g as f32 * (1.0 - k)) as u8;
+            px_out.b = (c.b as f32 * k + px_out.b as f32 * (1.0 - k)) as u8;
+            if c.a >= 128 {
+                *z = dist;
+            }
+        }
+    }
 }
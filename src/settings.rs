@@ -0,0 +1,35 @@
+use crate::framebuffer::{RenderRes, Upscale};
+use crate::postfx::Effect;
+use crate::textures::Filter;
+
+/// Preferencias del jugador, editables desde el menú de opciones.
+pub struct Settings {
+    pub invert_y: bool,         // mouse / stick hacia arriba mira hacia abajo
+    pub camera_motion: bool,    // balanceo al caminar y sacudidas de cámara
+    pub texture_filter: Filter, // calidad de muros, piso y techo (nearest es lo más rápido)
+    pub effects: Vec<Effect>,   // post-proceso activo (se aplica en el orden de `Effect::ALL`)
+    pub render_res: RenderRes,  // resolución interna del render 3D
+    pub upscale: Upscale,       // cómo se agranda ese render hasta la ventana
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self {
+            invert_y: false,
+            camera_motion: true,
+            texture_filter: Filter::Nearest,
+            effects: vec![Effect::Panic, Effect::Vignette, Effect::Grain],
+            render_res: RenderRes::R800x600,
+            upscale: Upscale::Aspect,
+        }
+    }
+
+    /// Prende o apaga un efecto de post-proceso.
+    pub fn toggle_effect(&mut self, effect: Effect) {
+        if let Some(k) = self.effects.iter().position(|&e| e == effect) {
+            self.effects.remove(k);
+        } else {
+            self.effects.push(effect);
+        }
+    }
+}
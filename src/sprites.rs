@@ -0,0 +1,224 @@
+use crate::flashlight::{Lighting, apply_light};
+use crate::framebuffer::FrameBuffer;
+use crate::maze::Maze;
+use crate::player::Player;
+use crate::render3d::{fog_amount, fog_mix};
+use crate::textures::Textures;
+use raylib::prelude::*;
+
+/// Billboard en el mundo: siempre mira a la cámara.
+#[derive(Clone, Debug)]
+pub struct Sprite {
+    pub key: char,            // clave de textura (PNG con alpha)
+    pub pos: Vector2,         // px, como `Player::pos`
+    pub scale: f32,           // alto en bloques (1 = del piso al techo)
+    pub offset: f32,          // altura de la base sobre el piso, en bloques
+    pub tint: Color,          // multiplica el color; la alpha del tinte escala la del texel
+    pub frame: usize,         // frame de la hoja de sprites (0 si es una imagen suelta)
+    pub heading: Option<f32>, // hacia dónde mira (rad); elige la vista en hojas direccionales
+}
+
+impl Sprite {
+    pub fn new(key: char, pos: Vector2) -> Self {
+        Self {
+            key,
+            pos,
+            scale: 1.0,
+            offset: 0.0,
+            tint: Color::WHITE,
+            frame: 0,
+            heading: None,
+        }
+    }
+
+    /// Parsea una línea de `[props]`: `clave x y [escala [altura]]`,
+    /// con posición en celdas (p. ej. `w 3.5 7.5 0.6`).
+    pub fn parse(line: &str, block_size: i32) -> Result<Option<Self>, String> {
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let key = match parts.first().map(|k| k.chars().collect::<Vec<_>>()) {
+            Some(k) if k.len() == 1 => k[0],
+            _ => return Err(format!("prop sin clave de textura: '{}'", line)),
+        };
+        if parts.len() < 3 || parts.len() > 5 {
+            return Err(format!(
+                "prop inválido (clave x y [escala [altura]]): '{}'",
+                line
+            ));
+        }
+        let num = |s: &str| {
+            s.parse::<f32>()
+                .map_err(|_| format!("número inválido en [props]: '{}'", s))
+        };
+        let bs = block_size as f32;
+        let mut sprite = Self::new(key, Vector2::new(num(parts[1])? * bs, num(parts[2])? * bs));
+        if let Some(s) = parts.get(3) {
+            sprite.scale = num(s)?;
+            if !sprite.scale.is_finite() || sprite.scale <= 0.0 {
+                return Err(format!("la escala del prop debe ser positiva: '{}'", line));
+            }
+        }
+        if let Some(o) = parts.get(4) {
+            sprite.offset = num(o)?;
+            if !sprite.offset.is_finite() || sprite.offset < 0.0 {
+                return Err(format!(
+                    "la altura del prop no puede ser negativa: '{}'",
+                    line
+                ));
+            }
+        }
+        Ok(Some(sprite))
+    }
+}
+
+/// Dibuja los sprites de atrás hacia adelante, recortados contra el
+/// z-buffer por píxel que devuelve `render3d`. `props` son los fijos del
+/// nivel y `actors` los que se mueven (el enemigo), mezclados al ordenar.
+pub fn render_sprites(
+    framebuffer: &mut FrameBuffer,
+    maze: &Maze,
+    player: &Player,
+    zbuffer: &[f32],
+    textures: &Textures,
+    props: &[Sprite],
+    actors: &[Sprite],
+) {
+    let dirx = player.a.cos();
+    let diry = player.a.sin();
+
+    // profundidad (perpendicular a la pantalla) de cada sprite visible
+    let mut order: Vec<(f32, &Sprite)> = props
+        .iter()
+        .chain(actors)
+        .map(|s| {
+            let perp = (s.pos.x - player.pos.x) * dirx + (s.pos.y - player.pos.y) * diry;
+            (perp, s)
+        })
+        .filter(|(perp, _)| *perp > 1.0) // detrás o demasiado cerca
+        .collect();
+    order.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let w = framebuffer.width;
+    let h = framebuffer.height;
+    let lighting = Lighting::new(player, maze, w, h);
+    for (perp, sprite) in order {
+        draw_sprite(
+            framebuffer,
+            maze,
+            player,
+            zbuffer,
+            textures,
+            &lighting,
+            sprite,
+            perp,
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_sprite(
+    framebuffer: &mut FrameBuffer,
+    maze: &Maze,
+    player: &Player,
+    zbuffer: &[f32],
+    textures: &Textures,
+    lighting: &Lighting,
+    sprite: &Sprite,
+    perp: f32,
+) {
+    let w = framebuffer.width;
+    let h = framebuffer.height;
+    let hw = w as f32 * 0.5;
+    let hh = h as f32 * 0.5;
+    let dist_to_proj = hw / (player.fov * 0.5).tan();
+    let bs = maze.block_size as f32;
+
+    // componente lateral (para x en pantalla)
+    let rightx = -player.a.sin();
+    let righty = player.a.cos();
+    let plane_len = (player.fov * 0.5).tan();
+    let side = (sprite.pos.x - player.pos.x) * rightx + (sprite.pos.y - player.pos.y) * righty;
+    let screen_x = hw * (1.0 + (side / (perp * plane_len)));
+
+    // el piso queda `eye_height` bloques por debajo de los ojos
+    let block_h = (bs * dist_to_proj) / perp;
+    let sprite_h = block_h * sprite.scale;
+    let horizon = hh + player.horizon_shift(w);
+    let bottom = horizon + block_h * (player.eye_height() - sprite.offset);
+    let top = bottom - sprite_h;
+
+    let tex = textures.get(sprite.key);
+    let frame = match sprite.heading {
+        Some(heading) if tex.directions > 1 => {
+            // ángulo entre hacia dónde mira y la dirección sprite -> cámara
+            let to_cam = (player.pos.y - sprite.pos.y).atan2(player.pos.x - sprite.pos.x);
+            let step = std::f32::consts::TAU / tex.directions as f32;
+            let rel = (heading - to_cam).rem_euclid(std::f32::consts::TAU);
+            let dir = (rel / step).round() as usize % tex.directions;
+            tex.directional_frame(sprite.frame, dir)
+        }
+        _ => sprite.frame,
+    };
+    let sprite_w = sprite_h * (tex.frame_w() as f32 / tex.frame_h() as f32);
+    let left = screen_x - sprite_w * 0.5;
+
+    // recorta a pantalla
+    let x0 = (left.floor() as i32).clamp(0, w - 1);
+    let x1 = ((screen_x + sprite_w * 0.5).ceil() as i32).clamp(0, w - 1);
+    let y0 = (top.floor() as i32).clamp(0, h - 1);
+    let y1 = (bottom.ceil() as i32).clamp(0, h - 1);
+    if x0 > x1 || y0 > y1 {
+        return;
+    }
+
+    let u_eps = 0.5 / tex.frame_w() as f32;
+    let v_eps = 0.5 / tex.frame_h() as f32;
+
+    // niebla del nivel, con la misma curva que los muros
+    let fog_t = fog_amount(perp);
+    let (wx, wy) = (sprite.pos.x / bs, sprite.pos.y / bs);
+    let tint = sprite.tint;
+
+    for sx in x0..=x1 {
+        let u = ((sx as f32 - left) / sprite_w).clamp(u_eps, 1.0 - u_eps);
+
+        for sy in y0..=y1 {
+            let idx = (sy * w + sx) as usize;
+            if zbuffer.get(idx).is_some_and(|&z| perp >= z) {
+                continue;
+            }
+            let v = ((sy as f32 - top) / sprite_h).clamp(v_eps, 1.0 - v_eps);
+
+            let c = tex.sample_frame(frame, u, v);
+            let alpha = c.a as u32 * tint.a as u32 / 255;
+            if alpha < 16 {
+                continue;
+            }
+
+            let mut out = Color::new(
+                (c.r as u32 * tint.r as u32 / 255) as u8,
+                (c.g as u32 * tint.g as u32 / 255) as u8,
+                (c.b as u32 * tint.b as u32 / 255) as u8,
+                255,
+            );
+            out = fog_mix(out, maze.fog, fog_t);
+            if !lighting.is_full() {
+                out = apply_light(out, lighting.at(sx as f32, sy as f32, perp, wx, wy));
+            }
+
+            if alpha < 255 {
+                // bordes semitransparentes: mezcla con lo que ya hay detrás
+                let dst = framebuffer.pixels[idx];
+                let k = alpha as f32 / 255.0;
+                out.r = (out.r as f32 * k + dst.r as f32 * (1.0 - k)) as u8;
+                out.g = (out.g as f32 * k + dst.g as f32 * (1.0 - k)) as u8;
+                out.b = (out.b as f32 * k + dst.b as f32 * (1.0 - k)) as u8;
+            }
+            // SAFETY: x0..=x1 y y0..=y1 ya están recortados a la pantalla
+            unsafe { framebuffer.put_pixel_unchecked(sx, sy, out) };
+        }
+    }
+}
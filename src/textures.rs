@@ -1,28 +1,220 @@
+use crate::manifest::{AnimationEntry, Manifest, TextureEntry};
 use raylib::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Cómo se muestrean las texturas de muros, piso y techo.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Filter {
+    Nearest,   // el texel más cercano (pixelado, parpadea de lejos)
+    Bilinear,  // mezcla de los 4 texels vecinos
+    Mipmapped, // bilineal sobre la copia reducida que toca por distancia
+}
+
+impl Filter {
+    /// Siguiente opción, para ciclar desde el menú.
+    pub fn next(self) -> Self {
+        match self {
+            Filter::Nearest => Filter::Bilinear,
+            Filter::Bilinear => Filter::Mipmapped,
+            Filter::Mipmapped => Filter::Nearest,
+        }
+    }
+}
+
+/// Copia reducida a la mitad (y a la mitad de esa...) de una textura.
+struct MipLevel {
+    w: i32,
+    h: i32,
+    pixels: Vec<Color>,
+}
+
+/// Animación con nombre dentro de una hoja de sprites.
+pub struct Animation {
+    pub frames: Vec<usize>, // índices en la grilla (fila por fila)
+    pub fps: f32,
+}
 
 pub struct CpuTexture {
     pub w: i32,
     pub h: i32,
     pub pixels: ImageColors,
+    pub cols: i32, // hoja de sprites: grilla de cols x rows frames (1x1 = imagen suelta)
+    pub rows: i32,
+    pub directions: usize, // vistas por ángulo (8 = una cada 45°); cada una ocupa un bloque de la grilla
+    animations: HashMap<String, Animation>,
+    mips: Vec<MipLevel>,    // niveles 1.. (el 0 es `pixels`), hasta 1x1
+    filter: Option<Filter>, // fijado en el manifiesto; None = el de opciones
 }
 impl CpuTexture {
-    pub fn from_path(path: &str) -> Self {
-        let img = Image::load_image(path).expect(path);
+    pub fn from_path(path: &str) -> Result<Self, String> {
+        if !Path::new(path).is_file() {
+            return Err(format!("no existe {}", path));
+        }
+        let img = Image::load_image(path).map_err(|e| format!("{}: {}", path, e))?;
+        Ok(Self::from_image(img))
+    }
+
+    fn from_image(img: Image) -> Self {
         let (w, h) = (img.width, img.height);
         let pixels = img.get_image_data();
-        Self { w, h, pixels }
+        let mips = build_mips(&pixels, w, h);
+        Self {
+            w,
+            h,
+            pixels,
+            cols: 1,
+            rows: 1,
+            directions: 1,
+            animations: HashMap::new(),
+            mips,
+            filter: None,
+        }
     }
+
     #[inline]
     pub fn sample(&self, u: f32, v: f32) -> Color {
         let x = ((u.clamp(0.0, 0.9999) * self.w as f32) as i32).clamp(0, self.w - 1);
         let y = ((v.clamp(0.0, 0.9999) * self.h as f32) as i32).clamp(0, self.h - 1);
         self.pixels[(y * self.w + x) as usize]
     }
+
+    /// Muestrea (u, v) con el filtro pedido. `lod` es log2 de cuántos texels
+    /// caen en un píxel de pantalla (0 = uno a uno); solo lo usa `Mipmapped`.
+    #[inline]
+    pub fn sample_filtered(&self, u: f32, v: f32, filter: Filter, lod: f32) -> Color {
+        match self.filter.unwrap_or(filter) {
+            Filter::Nearest => self.sample(u, v),
+            Filter::Bilinear => bilinear(&self.pixels, self.w, self.h, u, v),
+            Filter::Mipmapped => {
+                // nivel 0 = `pixels`; los demás vienen de la cadena
+                let level = ((lod + 0.5).max(0.0) as usize).min(self.mips.len());
+                match level.checked_sub(1) {
+                    Some(k) => {
+                        let mip = &self.mips[k];
+                        bilinear(&mip.pixels, mip.w, mip.h, u, v)
+                    }
+                    None => bilinear(&self.pixels, self.w, self.h, u, v),
+                }
+            }
+        }
+    }
+
+    /// Ancho de un frame (px).
+    #[inline]
+    pub fn frame_w(&self) -> i32 {
+        self.w / self.cols
+    }
+
+    /// Alto de un frame (px).
+    #[inline]
+    pub fn frame_h(&self) -> i32 {
+        self.h / self.rows
+    }
+
+    /// Muestrea (u, v) dentro del frame `frame` de la hoja.
+    #[inline]
+    pub fn sample_frame(&self, frame: usize, u: f32, v: f32) -> Color {
+        let n = (self.cols * self.rows) as usize;
+        let f = (frame % n) as i32;
+        let (fc, fr) = ((f % self.cols) as f32, (f / self.cols) as f32);
+        let u = (fc + u.clamp(0.0, 0.9999)) / self.cols as f32;
+        let v = (fr + v.clamp(0.0, 0.9999)) / self.rows as f32;
+        self.sample(u, v)
+    }
+
+    /// Frame de la vista `dir` (0 = de frente) equivalente a `frame`.
+    /// Las animaciones indexan dentro de una vista; las vistas van en bloques
+    /// consecutivos de la grilla.
+    #[inline]
+    pub fn directional_frame(&self, frame: usize, dir: usize) -> usize {
+        let per_dir = (self.cols * self.rows) as usize / self.directions;
+        frame % per_dir + (dir % self.directions) * per_dir
+    }
+
+    /// Frame de la animación `name` a los `t` segundos de empezar (en bucle).
+    /// Si la animación no existe devuelve el frame 0.
+    pub fn animation_frame(&self, name: &str, t: f32) -> usize {
+        match self.animations.get(name) {
+            Some(a) if !a.frames.is_empty() => {
+                let k = (t.max(0.0) * a.fps) as usize % a.frames.len();
+                a.frames[k]
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Mezcla los 4 texels alrededor de (u, v); repite la textura en los bordes,
+/// que es como se usan muros y pisos.
+#[inline]
+fn bilinear(pixels: &[Color], w: i32, h: i32, u: f32, v: f32) -> Color {
+    let x = u * w as f32 - 0.5;
+    let y = v * h as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = ((x0 as i32).rem_euclid(w), (y0 as i32).rem_euclid(h));
+    let (x1, y1) = ((x0 + 1) % w, (y0 + 1) % h);
+    let at = |x: i32, y: i32| pixels[(y * w + x) as usize];
+    let (c00, c10, c01, c11) = (at(x0, y0), at(x1, y0), at(x0, y1), at(x1, y1));
+    let mix = |a: u8, b: u8, c: u8, d: u8| {
+        let top = a as f32 + (b as f32 - a as f32) * fx;
+        let bot = c as f32 + (d as f32 - c as f32) * fx;
+        (top + (bot - top) * fy + 0.5) as u8
+    };
+    Color::new(
+        mix(c00.r, c10.r, c01.r, c11.r),
+        mix(c00.g, c10.g, c01.g, c11.g),
+        mix(c00.b, c10.b, c01.b, c11.b),
+        mix(c00.a, c10.a, c01.a, c11.a),
+    )
+}
+
+/// Cadena de mipmaps: cada nivel promedia bloques de 2x2 del anterior
+/// (pesando por alpha, para que lo transparente no oscurezca los bordes).
+fn build_mips(base: &[Color], w: i32, h: i32) -> Vec<MipLevel> {
+    let mut mips: Vec<MipLevel> = Vec::new();
+    let (mut pw, mut ph) = (w, h);
+    while pw > 1 || ph > 1 {
+        let prev = mips.last().map_or(base, |m| &m.pixels[..]);
+        let (nw, nh) = ((pw / 2).max(1), (ph / 2).max(1));
+        let mut pixels = Vec::with_capacity((nw * nh) as usize);
+        for y in 0..nh {
+            for x in 0..nw {
+                let mut sum = [0u32; 4];
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let sx = (x * 2 + dx).min(pw - 1);
+                    let sy = (y * 2 + dy).min(ph - 1);
+                    let c = prev[(sy * pw + sx) as usize];
+                    let a = c.a as u32;
+                    sum[0] += c.r as u32 * a;
+                    sum[1] += c.g as u32 * a;
+                    sum[2] += c.b as u32 * a;
+                    sum[3] += a;
+                }
+                let a = sum[3].max(1);
+                pixels.push(Color::new(
+                    (sum[0] / a) as u8,
+                    (sum[1] / a) as u8,
+                    (sum[2] / a) as u8,
+                    (sum[3] / 4) as u8,
+                ));
+            }
+        }
+        mips.push(MipLevel {
+            w: nw,
+            h: nh,
+            pixels,
+        });
+        (pw, ph) = (nw, nh);
+    }
+    mips
 }
 
 pub struct Textures {
     map: HashMap<char, CpuTexture>,
+    declared: HashSet<char>, // claves del manifiesto, aunque su imagen no haya cargado
     fallback: CpuTexture,
 }
 
@@ -36,32 +228,86 @@ impl Textures {
                 }
             }
         }
-        let (w, h) = (img.width, img.height);
-        let pixels = img.get_image_data();
         Self {
             map: HashMap::new(),
-            fallback: CpuTexture { w, h, pixels },
+            declared: HashSet::new(),
+            fallback: CpuTexture::from_image(img),
+        }
+    }
+
+    /// Carga una textura del manifiesto (imagen suelta u hoja de sprites).
+    fn load_entry(&mut self, e: &TextureEntry) -> Result<(), String> {
+        let mut tex = CpuTexture::from_path(&e.path)?;
+        if tex.w % e.cols != 0 || tex.h % e.rows != 0 {
+            return Err(format!(
+                "{}: {}x{} px no se corta en una hoja de {}x{}",
+                e.path, tex.w, tex.h, e.cols, e.rows
+            ));
+        }
+        tex.cols = e.cols;
+        tex.rows = e.rows;
+        tex.directions = e.directions;
+        tex.filter = e.filter;
+        self.map.insert(e.key, tex);
+        Ok(())
+    }
+
+    /// Registra una animación con nombre sobre una hoja ya cargada.
+    fn add_animation(&mut self, a: &AnimationEntry) -> Result<(), String> {
+        let Some(tex) = self.map.get_mut(&a.key) else {
+            return Ok(()); // la hoja no cargó: ya está entre los problemas
+        };
+        // los frames cuentan dentro de una vista
+        let per_dir = (tex.cols * tex.rows) as usize / tex.directions;
+        if let Some(f) = a.frames.iter().find(|&&f| f >= per_dir) {
+            return Err(format!(
+                "animación '{}' de '{}': frame {} fuera de la hoja ({} por vista)",
+                a.name, a.key, f, per_dir
+            ));
         }
+        let anim = Animation {
+            frames: a.frames.clone(),
+            fps: a.fps,
+        };
+        tex.animations.insert(a.name.clone(), anim);
+        Ok(())
     }
 
-    pub fn insert(&mut self, key: char, path: &str) {
-        self.map.insert(key, CpuTexture::from_path(path));
+    /// `true` si el manifiesto declara la clave (aunque se vea con el damero).
+    pub fn knows(&self, key: char) -> bool {
+        self.declared.contains(&key)
     }
+
     pub fn get(&self, key: char) -> &CpuTexture {
         self.map.get(&key).unwrap_or(&self.fallback)
     }
 
-    pub fn load_default() -> Self {
+    /// Carga las texturas del manifiesto y devuelve todos los problemas juntos
+    /// (líneas mal escritas, archivos que faltan, hojas mal cortadas). Lo que
+    /// no se pudo cargar queda con el damero de `fallback`, sin abortar.
+    pub fn load_manifest(path: &str) -> (Self, Vec<String>) {
         let mut t = Self::new();
-        t.insert('#', "assets/wall_normal.png"); // muros
-        t.insert('.', "assets/piso.png"); // piso (floor casting)
-        t.insert('C', "assets/door.png"); // puertas (como pared)
-        // t.insert('T', "assets/taylor_cursed.jpg"); 
-        t.insert('M', "assets/taylor_enemy.png"); // <-- sprite del ENEMIGO (PNG con alpha)
-        t.insert('A', "assets/wall_sangre.png"); // muros con sangre
-        // t.insert('B', "...");
-        t.insert('E', "assets/stairs.png");
-        t.insert('F', "assets/exit.png");
-        t
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => return (t, vec![format!("no se pudo leer {}: {}", path, e)]),
+        };
+        let (manifest, mut problems) = Manifest::parse(&text);
+        for e in &manifest.textures {
+            t.declared.insert(e.key);
+            if let Err(err) = t.load_entry(e) {
+                problems.push(format!("'{}': {}", e.key, err));
+            }
+        }
+        for a in &manifest.animations {
+            if !manifest.textures.iter().any(|e| e.key == a.key) {
+                problems.push(format!(
+                    "animación '{}' de '{}', que no está en [textures]",
+                    a.name, a.key
+                ));
+            } else if let Err(err) = t.add_animation(a) {
+                problems.push(err);
+            }
+        }
+        (t, problems)
     }
 }